@@ -3,11 +3,10 @@ extern crate bencher;
 use bencher::Bencher;
 use cellular_automaton::cell::Cell;
 use cellular_automaton::world::WPoint;
-use nalgebra::Point2;
 use std::mem::size_of_val;
 
 fn from_zero_point(bench: &mut Bencher) {
-    let point = WPoint::new(0.0, 0.0, 0.0, 0.0, 0.0, 0.0);
+    let point = WPoint::new(0.0, 0.0, 0.0, 0.0);
     bench.iter(|| {
         let _c: Cell = Cell::from((point, 0, 0));
     });
@@ -18,7 +17,6 @@ fn from_zero_point(bench: &mut Bencher) {
 fn into_zero_point(bench: &mut Bencher) {
     let cell = Cell {
         color: [0.0, 0.0, 0.0, 0.0],
-        top_left: Point2::new(0.0, 0.0),
         at: (0, 0)
     };
 
@@ -31,7 +29,7 @@ fn into_zero_point(bench: &mut Bencher) {
 }
 
 fn from_one_point(bench: &mut Bencher) {
-    let point = WPoint::new(1.0, 1.0, 1.0, 1.0, 1.0, 1.0);
+    let point = WPoint::new(1.0, 1.0, 1.0, 1.0);
     bench.iter(|| {
         let _c: Cell = Cell::from((point, 1, 1));
     });
@@ -42,7 +40,6 @@ fn from_one_point(bench: &mut Bencher) {
 fn into_one_point(bench: &mut Bencher) {
     let cell = Cell {
         color: [1.0, 1.0, 1.0, 1.0],
-        top_left: Point2::new(1.0, 1.0),
         at: (1, 1)
     };
 
@@ -1,11 +1,13 @@
 #[macro_use]
 extern crate bencher;
 use bencher::Bencher;
+use cellular_automaton::cell::{Cell, DEAD, SUPER_NOVA};
+use cellular_automaton::rules::{LargerThanLife, LifeLike, Rule};
 use cellular_automaton::world::World;
 use std::mem::size_of_val;
 
 fn one_one_get_cells(bench: &mut Bencher) {
-    let mut world = World::new(1, 1, 1.0);
+    let mut world = World::new(1, 1);
     world.mirror_edge(1);
 
     bench.iter(|| {
@@ -16,7 +18,7 @@ fn one_one_get_cells(bench: &mut Bencher) {
 }
 
 fn hundred_one_get_cells(bench: &mut Bencher) {
-    let mut world = World::new(100, 100, 1.0);
+    let mut world = World::new(100, 100);
     world.mirror_edge(1);
 
     bench.iter(|| {
@@ -27,7 +29,7 @@ fn hundred_one_get_cells(bench: &mut Bencher) {
 }
 
 fn hundred_three_get_cells(bench: &mut Bencher) {
-    let mut world = World::new(100, 100, 1.0);
+    let mut world = World::new(100, 100);
     world.mirror_edge(3);
 
     bench.iter(|| {
@@ -38,7 +40,7 @@ fn hundred_three_get_cells(bench: &mut Bencher) {
 }
 
 fn one_one_update(bench: &mut Bencher) {
-    let mut world = World::new(1, 1, 1.0);
+    let mut world = World::new(1, 1);
     world.mirror_edge(1);
 
     bench.iter(|| {
@@ -49,7 +51,7 @@ fn one_one_update(bench: &mut Bencher) {
 }
 
 fn hundred_one_update(bench: &mut Bencher) {
-    let mut world = World::new(100, 100, 1.0);
+    let mut world = World::new(100, 100);
     world.mirror_edge(1);
 
     bench.iter(|| {
@@ -60,7 +62,7 @@ fn hundred_one_update(bench: &mut Bencher) {
 }
 
 fn hundred_three_update(bench: &mut Bencher) {
-    let mut world = World::new(100, 100, 1.0);
+    let mut world = World::new(100, 100);
     world.mirror_edge(3);
 
     bench.iter(|| {
@@ -70,8 +72,84 @@ fn hundred_three_update(bench: &mut Bencher) {
     bench.bytes = size_of_val(&world) as u64;
 }
 
+// a fixed soup, about a third of the cells alive
+fn soup(world: &mut World) {
+    for cell in world.get_cells() {
+        let (row, col) = cell.at;
+        if (row * 7 + col * 13) % 3 == 0 {
+            world.write(Cell { color: SUPER_NOVA, ..cell });
+        }
+    }
+}
+
+// a generation of life the way next and its writes step it
+fn hundred_life_next(bench: &mut Bencher) {
+    let mut world = World::new(100, 100);
+    world.mirror_edge(1);
+    soup(&mut world);
+
+    bench.iter(|| {
+        let writes = world.next(|surroundings, cell| {
+            let alive = surroundings.iter().filter(|n| n.is_alive()).count();
+            match (cell.is_alive(), alive) {
+                (true, 2) | (_, 3) => Some(Cell { color: SUPER_NOVA, ..cell }),
+                _ => Some(Cell { color: DEAD, ..cell }),
+            }
+        });
+        for cell in writes {
+            world.write(cell);
+        }
+    });
+
+    bench.bytes = size_of_val(&world) as u64;
+}
+
+fn hundred_life_step(bench: &mut Bencher) {
+    let mut world = World::new(100, 100);
+    let rule = LifeLike::conway();
+    soup(&mut world);
+
+    bench.iter(|| {
+        world.step(&rule);
+    });
+
+    bench.bytes = size_of_val(&world) as u64;
+}
+
+fn hundred_five_step(bench: &mut Bencher) {
+    let mut world = World::new(100, 100);
+    let rule = LargerThanLife::bugs();
+    world.mirror_edge(rule.radius());
+    soup(&mut world);
+
+    bench.iter(|| {
+        world.step(&rule);
+    });
+
+    bench.bytes = size_of_val(&world) as u64;
+}
+
+// a glider alone in a large world, the steps only visit the tiles around it
+fn four_hundred_glider_step(bench: &mut Bencher) {
+    let mut world = World::new(400, 400);
+    let rule = LifeLike::conway();
+    for &(row, col) in [(0, 1), (1, 2), (2, 0), (2, 1), (2, 2)].iter() {
+        let cell = world.find_cell_at(200 + row, 200 + col).unwrap();
+        world.write(Cell { color: SUPER_NOVA, ..cell });
+    }
+    world.step(&rule);
+
+    bench.iter(|| {
+        world.step(&rule);
+    });
+
+    bench.bytes = size_of_val(&world) as u64;
+}
+
 benchmark_group!(get_cells, one_one_get_cells, hundred_one_get_cells);
 benchmark_group!(mirror_edge_get_cells, hundred_one_get_cells, hundred_three_get_cells);
 benchmark_group!(update, one_one_update, hundred_one_update);
 benchmark_group!(mirror_edge_update, hundred_one_update, hundred_three_update);
-benchmark_main!(get_cells, mirror_edge_get_cells, update, mirror_edge_update);
\ No newline at end of file
+benchmark_group!(step, hundred_life_next, hundred_life_step, hundred_five_step);
+benchmark_group!(quiet_step, hundred_life_step, four_hundred_glider_step);
+benchmark_main!(get_cells, mirror_edge_get_cells, update, mirror_edge_update, step, quiet_step);
@@ -0,0 +1,373 @@
+use crate::cell::{Cell, DEAD, SUPER_NOVA};
+use crate::rules::{parse_presets, preset_name_by, RuleParseError};
+use crate::world::World;
+use graphics::types::Color;
+use std::fmt;
+use std::str::FromStr;
+
+pub const PRESETS: [(&str, &str); 5] = [
+    ("Langton's Ant", "RL"),
+    ("Symmetric Ant", "LLRR"),
+    ("Square Filler", "LRRRRRLLR"),
+    ("Highway Builder", "RLLR"),
+    ("Fibonacci Spiral", "{{{1,8,1},{1,8,1}},{{1,2,1},{0,1,0}}}"),
+];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum Heading {
+    #[default]
+    North,
+    East,
+    South,
+    West,
+}
+
+impl Heading {
+    pub fn turn(&self, turn: Turn) -> Heading {
+        let quarters = match turn {
+            Turn::None => 0,
+            Turn::Right => 1,
+            Turn::UTurn => 2,
+            Turn::Left => 3,
+        };
+        let headings = [Heading::North, Heading::East, Heading::South, Heading::West];
+        headings[(*self as usize + quarters) % headings.len()]
+    }
+
+    // (row, col) step one cell ahead
+    pub fn delta(&self) -> (isize, isize) {
+        match self {
+            Heading::North => (-1, 0),
+            Heading::East => (0, 1),
+            Heading::South => (1, 0),
+            Heading::West => (0, -1),
+        }
+    }
+}
+
+// the turn codes of the turmite notation, 1 none, 2 right, 4 u-turn, 8 left
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Turn {
+    None,
+    Right,
+    UTurn,
+    Left,
+}
+
+impl Turn {
+    pub fn code(&self) -> usize {
+        match self {
+            Turn::None => 1,
+            Turn::Right => 2,
+            Turn::UTurn => 4,
+            Turn::Left => 8,
+        }
+    }
+
+    pub fn from_code(code: usize) -> Option<Turn> {
+        match code {
+            1 => Some(Turn::None),
+            2 => Some(Turn::Right),
+            4 => Some(Turn::UTurn),
+            8 => Some(Turn::Left),
+            _ => None,
+        }
+    }
+
+    fn from_letter(letter: char) -> Option<Turn> {
+        match letter.to_ascii_uppercase() {
+            'N' => Some(Turn::None),
+            'R' => Some(Turn::Right),
+            'U' => Some(Turn::UTurn),
+            'L' => Some(Turn::Left),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Transition {
+    pub write: usize,
+    pub turn: Turn,
+    pub next: usize,
+}
+
+// A turing machine on the grid, for every internal state and cell color
+// it writes a color, turns and changes state.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Turmite {
+    name: String,
+    table: Vec<Vec<Transition>>,
+}
+
+impl Turmite {
+    pub fn new(table: Vec<Vec<Transition>>) -> Result<Self, RuleParseError> {
+        let states = table.len();
+        let colors = table.first().map(|row| row.len()).unwrap_or(0);
+        if states == 0 {
+            return Err(RuleParseError::Empty);
+        }
+        if colors < 2 {
+            return Err(RuleParseError::StatesOutOfRange(colors));
+        }
+
+        for row in table.iter() {
+            if row.len() != colors {
+                return Err(RuleParseError::StatesOutOfRange(row.len()));
+            }
+            for transition in row.iter() {
+                if transition.write >= colors {
+                    return Err(RuleParseError::StatesOutOfRange(transition.write));
+                }
+                if transition.next >= states {
+                    return Err(RuleParseError::StatesOutOfRange(transition.next));
+                }
+            }
+        }
+
+        let mut turmite = Self {
+            name: String::new(),
+            table,
+        };
+
+        let rulestring = turmite.to_string();
+        turmite.name = preset_name_by(&PRESETS, &rulestring, |preset| {
+            Self::parse_rulestring(preset).map(|table| notation(&table)).as_ref() == Ok(&rulestring)
+        });
+
+        Ok(turmite)
+    }
+
+    pub fn langtons_ant() -> Self {
+        "RL".parse().unwrap()
+    }
+
+    pub fn presets() -> Vec<Self> {
+        parse_presets(&PRESETS)
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn states(&self) -> usize {
+        self.table.len()
+    }
+
+    pub fn colors(&self) -> usize {
+        self.table[0].len()
+    }
+
+    pub fn transition(&self, state: usize, color: usize) -> Transition {
+        self.table[state % self.states()][color % self.colors()]
+    }
+
+    // colors are encoded in the alpha chanel the same way elementary rules do
+    pub fn color_index(&self, cell: &Cell) -> usize {
+        if !cell.is_alive() {
+            return 0;
+        }
+        let [_, _, _, a] = cell.color;
+        let top = self.colors() - 1;
+        ((a * top as f32).round() as usize).clamp(1, top)
+    }
+
+    pub fn color_of(&self, index: usize) -> Color {
+        match index {
+            0 => DEAD,
+            i if i + 1 == self.colors() => SUPER_NOVA,
+            i => [1.0, 1.0, 1.0, i as f32 / (self.colors() - 1) as f32],
+        }
+    }
+
+    fn parse_rulestring(s: &str) -> Result<Vec<Vec<Transition>>, RuleParseError> {
+        if s.starts_with('{') {
+            Self::parse_table(s)
+        } else {
+            Self::parse_letters(s)
+        }
+    }
+
+    // reads the numbers of "{{{1,2,0},{0,8,0}}}" as states of colors of transitions
+    fn parse_table(s: &str) -> Result<Vec<Vec<Transition>>, RuleParseError> {
+        let mut table = Vec::new();
+        let mut state = Vec::new();
+        let mut numbers = Vec::new();
+        let mut number = String::new();
+        let mut depth = 0;
+
+        for c in s.chars().filter(|c| !c.is_whitespace()) {
+            if c.is_ascii_digit() && depth == 3 {
+                number.push(c);
+                continue;
+            }
+            if !number.is_empty() {
+                numbers.push(
+                    number
+                        .parse::<usize>()
+                        .map_err(|_| RuleParseError::CountOutOfRange(usize::MAX))?,
+                );
+                number.clear();
+            }
+
+            match c {
+                '{' if depth < 3 => depth += 1,
+                ',' if depth > 0 => {}
+                '}' if depth == 3 => {
+                    let (write, code, next) = match numbers[..] {
+                        [write, code, next] => (write, code, next),
+                        _ => return Err(RuleParseError::UnexpectedChar('}')),
+                    };
+                    let turn = Turn::from_code(code).ok_or(RuleParseError::CountOutOfRange(code))?;
+                    state.push(Transition { write, turn, next });
+                    numbers.clear();
+                    depth -= 1;
+                }
+                '}' if depth == 2 => {
+                    table.push(std::mem::take(&mut state));
+                    depth -= 1;
+                }
+                '}' if depth == 1 => depth -= 1,
+                c => return Err(RuleParseError::UnexpectedChar(c)),
+            }
+        }
+
+        if depth != 0 {
+            return Err(RuleParseError::MissingSection('}'));
+        }
+
+        Ok(table)
+    }
+
+    // an ant turning by the letter of every color and painting the next one
+    fn parse_letters(s: &str) -> Result<Vec<Vec<Transition>>, RuleParseError> {
+        let colors = s.chars().count();
+        let state = s
+            .chars()
+            .enumerate()
+            .map(|(color, letter)| {
+                let turn = Turn::from_letter(letter).ok_or(RuleParseError::UnexpectedChar(letter))?;
+                Ok(Transition {
+                    write: (color + 1) % colors,
+                    turn,
+                    next: 0,
+                })
+            })
+            .collect::<Result<Vec<Transition>, _>>()?;
+
+        Ok(vec![state])
+    }
+}
+
+impl FromStr for Turmite {
+    type Err = RuleParseError;
+
+    // accepts the turmite notation "{{{1,2,0},{0,8,0}}}" and ant letters "RL", "LLRR"
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if s.is_empty() {
+            return Err(RuleParseError::Empty);
+        }
+
+        Self::new(Self::parse_rulestring(s)?)
+    }
+}
+
+fn notation(table: &[Vec<Transition>]) -> String {
+    let states: Vec<String> = table
+        .iter()
+        .map(|state| {
+            let transitions: Vec<String> = state
+                .iter()
+                .map(|t| format!("{{{},{},{}}}", t.write, t.turn.code(), t.next))
+                .collect();
+            format!("{{{}}}", transitions.join(","))
+        })
+        .collect();
+    format!("{{{}}}", states.join(","))
+}
+
+impl fmt::Display for Turmite {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", notation(&self.table))
+    }
+}
+
+// An agent walking over the world, it turns away from borders it can't cross.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Ant {
+    pub at: (usize, usize),
+    pub heading: Heading,
+    pub state: usize,
+}
+
+impl Ant {
+    pub fn new(at: (usize, usize), heading: Heading) -> Self {
+        Self {
+            at,
+            heading,
+            state: 0,
+        }
+    }
+
+    pub fn step(&mut self, turmite: &Turmite, world: &mut World) {
+        let (row, col) = self.at;
+        let cell = match world.find_cell_at(row, col) {
+            Some(cell) => cell,
+            None => return,
+        };
+
+        let Transition { write, turn, next } = turmite.transition(self.state, turmite.color_index(&cell));
+        world.write(Cell {
+            color: turmite.color_of(write),
+            ..cell
+        });
+        self.heading = self.heading.turn(turn);
+        self.state = next;
+
+        let (d_row, d_col) = self.heading.delta();
+        match world
+            .boundary()
+            .locate(row as isize + d_row, col as isize + d_col, world.rows(), world.cols())
+        {
+            Some(at) => self.at = at,
+            None => self.heading = self.heading.turn(Turn::UTurn),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn presets_round_trip() {
+        for (name, rulestring) in PRESETS.iter() {
+            let turmite: Turmite = rulestring.parse().unwrap();
+            assert_eq!(turmite.name(), *name);
+            assert_eq!(turmite.to_string().parse::<Turmite>().unwrap(), turmite);
+        }
+    }
+
+    #[test]
+    fn letters_match_the_table() {
+        let letters: Turmite = "RL".parse().unwrap();
+        let table: Turmite = " {{{1, 2, 0}, {0, 8, 0}}} ".parse().unwrap();
+        assert_eq!(letters, table);
+        assert_eq!(letters.to_string(), "{{{1,2,0},{0,8,0}}}");
+    }
+
+    #[test]
+    fn rejects_bad_rulestrings() {
+        let error = |s: &str| s.parse::<Turmite>().unwrap_err();
+        assert_eq!(error(""), RuleParseError::Empty);
+        assert_eq!(error("RX"), RuleParseError::UnexpectedChar('X'));
+        assert_eq!(error("R"), RuleParseError::StatesOutOfRange(1));
+        assert_eq!(error("{{{1,2,0},{0,8,0}}"), RuleParseError::MissingSection('}'));
+        assert_eq!(error("{{{1,3,0},{0,8,0}}}"), RuleParseError::CountOutOfRange(3));
+        assert_eq!(error("{{{1,2},{0,8,0}}}"), RuleParseError::UnexpectedChar('}'));
+        assert_eq!(error("{{{2,2,0},{0,8,0}}}"), RuleParseError::StatesOutOfRange(2));
+        assert_eq!(error("{{{1,2,1},{0,8,0}}}"), RuleParseError::StatesOutOfRange(1));
+        assert_eq!(error("{{{1,2,0},{0,8,0}}},"), RuleParseError::UnexpectedChar(','));
+    }
+}
@@ -5,7 +5,7 @@ use glutin_window::GlutinWindow;
 use piston::window::WindowSettings;
 // use piston::input::{Input, ResizeArgs};
 // use piston::{Event};
-// use piston::AdvancedWindow;
+use piston::AdvancedWindow;
 
 pub struct App {
     gl: GlGraphics, // OpenGL drawing backend.
@@ -16,7 +16,7 @@ pub struct App {
 const OPEN_GL: OpenGL = OpenGL::V3_2;
 
 impl App {
-    fn new() -> Self {
+    fn new(seed: Option<u64>) -> Self {
         let window: GlutinWindow = WindowSettings::new("cellulose", [0, 0])
             .graphics_api(OPEN_GL)
             .exit_on_esc(true)
@@ -24,7 +24,10 @@ impl App {
             .build()
             .unwrap();
 
-        let world_controller = WorldController::new();
+        let mut world_controller = WorldController::new();
+        if let Some(seed) = seed {
+            world_controller.set_seed(seed);
+        }
 
         Self {
             gl: GlGraphics::new(OPEN_GL),
@@ -35,12 +38,18 @@ impl App {
 
     
 
-    pub fn start() {
-        let mut app = Self::new();
+    // runs reproduce when started with the same seed
+    pub fn start(seed: Option<u64>) {
+        let mut app = Self::new(seed);
         
         let mut events = Events::new(EventSettings::new());
         while let Some(e) = events.next(&mut app.window) {
             app.world_controller.handle_event(&e, &mut app.gl);
+
+            let title = app.world_controller.title();
+            if title != app.window.get_title() {
+                app.window.set_title(title);
+            }
         }
     }
 
@@ -1,6 +1,15 @@
 use cellular_automaton::app::App;
+use std::env;
 
 fn main() {
+    // ca --seed 42
+    let args: Vec<String> = env::args().collect();
+    let seed = args
+        .iter()
+        .position(|arg| arg == "--seed")
+        .and_then(|at| args.get(at + 1))
+        .and_then(|seed| seed.parse().ok());
+
     // Change this to OpenGL::V2_1 if not working.
-    App::start();
+    App::start(seed);
 }
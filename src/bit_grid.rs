@@ -0,0 +1,168 @@
+use crate::boundary::Boundary;
+
+const WORD: usize = 64;
+
+// One bit per cell with a one cell halo all around that the boundary fills
+// before every step. Columns are packed into 64 cell words, the same order
+// the world's matrix keeps its cells in.
+#[derive(Clone, Debug, PartialEq)]
+pub struct BitGrid {
+    rows: usize,
+    cols: usize,
+    words: usize,
+    bits: Vec<u64>,
+}
+
+// adds a one bit input to bit sliced counters
+fn add(sum: &mut [u64; 4], input: u64) {
+    let mut carry = input;
+    for bit in sum.iter_mut() {
+        let next = *bit & carry;
+        *bit ^= carry;
+        carry = next;
+    }
+}
+
+impl BitGrid {
+    pub fn new(rows: usize, cols: usize) -> Self {
+        let words = (rows + 2).div_ceil(WORD);
+        Self {
+            rows,
+            cols,
+            words,
+            bits: vec![0; (cols + 2) * words],
+        }
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    // (word, bit) of a padded position
+    fn index(&self, row: usize, col: usize) -> (usize, u64) {
+        (col * self.words + row / WORD, 1 << (row % WORD))
+    }
+
+    fn get_padded(&self, row: usize, col: usize) -> bool {
+        let (word, bit) = self.index(row, col);
+        self.bits[word] & bit != 0
+    }
+
+    fn set_padded(&mut self, row: usize, col: usize, alive: bool) {
+        let (word, bit) = self.index(row, col);
+        if alive {
+            self.bits[word] |= bit;
+        } else {
+            self.bits[word] &= !bit;
+        }
+    }
+
+    pub fn get(&self, row: usize, col: usize) -> bool {
+        self.get_padded(row + 1, col + 1)
+    }
+
+    pub fn set(&mut self, row: usize, col: usize, alive: bool) {
+        self.set_padded(row + 1, col + 1, alive);
+    }
+
+    // the halo mirrors the cells the boundary maps it to, fixed borders are dead or alive
+    pub fn fill_halo(&mut self, boundary: Boundary) {
+        let (rows, cols) = (self.rows as isize, self.cols as isize);
+        let border = boundary == Boundary::Alive;
+
+        let mut fill = |row: isize, col: isize| {
+            let alive = match boundary.locate(row, col, self.rows, self.cols) {
+                Some((row, col)) => self.get(row, col),
+                None => border,
+            };
+            self.set_padded((row + 1) as usize, (col + 1) as usize, alive);
+        };
+
+        for col in -1..=cols {
+            fill(-1, col);
+            fill(rows, col);
+        }
+        for row in 0..rows {
+            fill(row, -1);
+            fill(row, cols);
+        }
+    }
+
+    // one generation of a Moore rule into a grid of the same size,
+    // bit n of birth and survival allows n neighbors
+    pub fn step(&self, birth: u16, survival: u16, next: &mut BitGrid) {
+        let words = self.words;
+
+        for col in 1..=self.cols {
+            let line = |c: usize| &self.bits[c * words..(c + 1) * words];
+            let lines = [(line(col - 1), true), (line(col), false), (line(col + 1), true)];
+
+            for w in 0..words {
+                let mut sum = [0u64; 4];
+                for &(line, with_middle) in lines.iter() {
+                    let word = line[w];
+                    let before = if w > 0 { line[w - 1] } else { 0 };
+                    let after = if w + 1 < words { line[w + 1] } else { 0 };
+                    // bit i gets the cells above and below it
+                    add(&mut sum, word << 1 | before >> (WORD - 1));
+                    add(&mut sum, word >> 1 | after << (WORD - 1));
+                    if with_middle {
+                        add(&mut sum, word);
+                    }
+                }
+
+                let alive = lines[1].0[w];
+                let mut result = 0;
+                for n in 0..=8 {
+                    let born = birth >> n & 1 != 0;
+                    let survives = survival >> n & 1 != 0;
+                    if !born && !survives {
+                        continue;
+                    }
+                    let mut exactly = !0u64;
+                    for (i, bit) in sum.iter().enumerate() {
+                        exactly &= if n >> i & 1 != 0 { *bit } else { !*bit };
+                    }
+                    if born {
+                        result |= exactly & !alive;
+                    }
+                    if survives {
+                        result |= exactly & alive;
+                    }
+                }
+                next.bits[col * words + w] = result;
+            }
+
+            // the halo is refilled before the next step
+            next.set_padded(0, col, false);
+            for row in self.rows + 1..words * WORD {
+                next.set_padded(row, col, false);
+            }
+        }
+    }
+
+    // calls change with the row, col and new state of the cells that differ
+    // from another grid of the same size, column after column
+    pub fn changes<F>(&self, next: &BitGrid, mut change: F)
+    where
+        F: FnMut(usize, usize, bool),
+    {
+        for col in 1..=self.cols {
+            for w in 0..self.words {
+                let index = col * self.words + w;
+                let mut diff = self.bits[index] ^ next.bits[index];
+                while diff != 0 {
+                    let row = w * WORD + diff.trailing_zeros() as usize;
+                    diff &= diff - 1;
+                    if row >= 1 && row <= self.rows {
+                        change(row - 1, col - 1, next.get_padded(row, col));
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,158 @@
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Boundary {
+    #[default]
+    Torus,
+    Dead,
+    Alive,
+    Reflective,
+    KleinBottle,
+    CrossSurface,
+    Infinite,
+}
+
+impl Boundary {
+    pub const ALL: [Boundary; 7] = [
+        Boundary::Torus,
+        Boundary::Dead,
+        Boundary::Alive,
+        Boundary::Reflective,
+        Boundary::KleinBottle,
+        Boundary::CrossSurface,
+        Boundary::Infinite,
+    ];
+
+    pub fn name(&self) -> &str {
+        match self {
+            Boundary::Torus => "torus",
+            Boundary::Dead => "dead border",
+            Boundary::Alive => "live border",
+            Boundary::Reflective => "reflective",
+            Boundary::KleinBottle => "Klein bottle",
+            Boundary::CrossSurface => "cross-surface",
+            Boundary::Infinite => "infinite",
+        }
+    }
+
+    pub fn next(&self) -> Self {
+        let at = Self::ALL.iter().position(|b| b == self).unwrap();
+        Self::ALL[(at + 1) % Self::ALL.len()]
+    }
+
+    // Where a cell outside of the world at (row, col) reads its state from,
+    // None when it lies on a fixed border.
+    pub fn locate(&self, row: isize, col: isize, rows: usize, cols: usize) -> Option<(usize, usize)> {
+        if rows == 0 || cols == 0 {
+            return None;
+        }
+
+        let (rows, cols) = (rows as isize, cols as isize);
+        let inside = row >= 0 && row < rows && col >= 0 && col < cols;
+
+        if inside {
+            return Some((row as usize, col as usize));
+        }
+
+        let wrap = |at: isize, size: isize| (at.rem_euclid(size), at.div_euclid(size) % 2 != 0);
+        let reflect = |at: isize, size: isize| {
+            let at = at.rem_euclid(size * 2);
+            if at < size {
+                at
+            } else {
+                size * 2 - 1 - at
+            }
+        };
+
+        let (row, col) = match self {
+            Boundary::Dead | Boundary::Alive | Boundary::Infinite => return None,
+            Boundary::Torus => (row.rem_euclid(rows), col.rem_euclid(cols)),
+            Boundary::Reflective => (reflect(row, rows), reflect(col, cols)),
+            Boundary::KleinBottle => {
+                let (row, twisted) = wrap(row, rows);
+                let col = col.rem_euclid(cols);
+                (row, if twisted { cols - 1 - col } else { col })
+            }
+            Boundary::CrossSurface => {
+                let (wrapped_row, twisted_col) = wrap(row, rows);
+                let (wrapped_col, twisted_row) = wrap(col, cols);
+                (
+                    if twisted_row { rows - 1 - wrapped_row } else { wrapped_row },
+                    if twisted_col { cols - 1 - wrapped_col } else { wrapped_col },
+                )
+            }
+        };
+
+        Some((row as usize, col as usize))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // cells around a 4x5 world: above, below, left, right and two opposite corners
+    const OUTSIDE: [(isize, isize); 6] = [(-1, 1), (4, 1), (2, -1), (2, 5), (-1, -1), (4, 5)];
+
+    fn locate_outside(boundary: Boundary) -> Vec<Option<(usize, usize)>> {
+        OUTSIDE
+            .iter()
+            .map(|&(row, col)| boundary.locate(row, col, 4, 5))
+            .collect()
+    }
+
+    #[test]
+    fn cells_inside_stay_put() {
+        for boundary in Boundary::ALL.iter() {
+            assert_eq!(boundary.locate(0, 0, 4, 5), Some((0, 0)), "{:?}", boundary);
+            assert_eq!(boundary.locate(3, 4, 4, 5), Some((3, 4)), "{:?}", boundary);
+            assert_eq!(boundary.locate(0, 0, 0, 5), None, "{:?}", boundary);
+        }
+    }
+
+    #[test]
+    fn fixed_borders_have_no_cell() {
+        for &boundary in [Boundary::Dead, Boundary::Alive, Boundary::Infinite].iter() {
+            assert_eq!(locate_outside(boundary), vec![None; OUTSIDE.len()], "{:?}", boundary);
+        }
+    }
+
+    #[test]
+    fn torus_wraps() {
+        let expected = [(3, 1), (0, 1), (2, 4), (2, 0), (3, 4), (0, 0)];
+        assert_eq!(
+            locate_outside(Boundary::Torus),
+            expected.iter().copied().map(Some).collect::<Vec<_>>()
+        );
+        assert_eq!(Boundary::Torus.locate(-9, 12, 4, 5), Some((3, 2)));
+    }
+
+    #[test]
+    fn reflective_mirrors_the_edge_cells() {
+        let expected = [(0, 1), (3, 1), (2, 0), (2, 4), (0, 0), (3, 4)];
+        assert_eq!(
+            locate_outside(Boundary::Reflective),
+            expected.iter().copied().map(Some).collect::<Vec<_>>()
+        );
+        // two cells out reads the second cell in
+        assert_eq!(Boundary::Reflective.locate(-2, 6, 4, 5), Some((1, 3)));
+    }
+
+    #[test]
+    fn klein_bottle_twists_rows_crossing_the_top_or_bottom() {
+        let expected = [(3, 3), (0, 3), (2, 4), (2, 0), (3, 0), (0, 4)];
+        assert_eq!(
+            locate_outside(Boundary::KleinBottle),
+            expected.iter().copied().map(Some).collect::<Vec<_>>()
+        );
+        // crossing twice untwists
+        assert_eq!(Boundary::KleinBottle.locate(-5, 1, 4, 5), Some((3, 1)));
+    }
+
+    #[test]
+    fn cross_surface_twists_both_ways() {
+        let expected = [(3, 3), (0, 3), (1, 4), (1, 0), (0, 0), (3, 4)];
+        assert_eq!(
+            locate_outside(Boundary::CrossSurface),
+            expected.iter().copied().map(Some).collect::<Vec<_>>()
+        );
+    }
+}
@@ -1,14 +1,43 @@
 use crate::world::WPoint;
 use conv::{ApproxFrom, ValueFrom};
 use graphics::types::Color;
-use nalgebra::Point2;
 
 type CellRepresntation = (WPoint, usize, usize);
 
+pub const SUPER_NOVA: Color = [1.0; 4];
+pub const DEAD: Color = [0.0; 4];
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Chanels {
+    Red,
+    Green,
+    Blue,
+}
+
+impl Chanels {
+    pub const ALL: [Chanels; 3] = [Chanels::Red, Chanels::Green, Chanels::Blue];
+
+    pub fn index(&self) -> usize {
+        match self {
+            Chanels::Red => 0,
+            Chanels::Green => 1,
+            Chanels::Blue => 2,
+        }
+    }
+
+    // rock-paper-scissors order, red eats green, green eats blue, blue eats red
+    pub fn predator(&self) -> Chanels {
+        match self {
+            Chanels::Red => Chanels::Blue,
+            Chanels::Green => Chanels::Red,
+            Chanels::Blue => Chanels::Green,
+        }
+    }
+}
+
 #[derive(Clone, Debug, Copy, PartialEq)]
 pub struct Cell {
     pub color: Color,
-    pub top_left: Point2<f64>,
     pub at: (usize, usize),
 }
 
@@ -18,8 +47,6 @@ impl From<CellRepresntation> for Cell {
         let g = w_point[1];
         let b = w_point[2];
         let a = w_point[3];
-        let x = w_point[4];
-        let y = w_point[5];
 
         Cell {
             color: [
@@ -28,43 +55,61 @@ impl From<CellRepresntation> for Cell {
                 f32::approx_from(b).unwrap(),
                 f32::approx_from(a).unwrap(),
             ],
-            top_left: Point2::new(x, y),
             at: (row, col),
         }
     }
 }
 
-impl Into<WPoint> for Cell {
-    fn into(self) -> WPoint {
+impl Cell {
+    pub fn is_alive(&self) -> bool {
+        self.is_chanel_alive(&Chanels::Red)
+            || self.is_chanel_alive(&Chanels::Green)
+            || self.is_chanel_alive(&Chanels::Blue)
+    }
+
+    pub fn is_super_nova(&self) -> bool {
+        let [r, g, b, a] = self.color;
+        (r + g + b) * a >= 3.0
+    }
+
+    pub fn chanel(&self, ch: &Chanels) -> f32 {
+        self.color[ch.index()] * self.color[3]
+    }
+
+    pub fn is_chanel_alive(&self, ch: &Chanels) -> bool {
+        self.chanel(ch) > 0.0
+    }
+
+    pub fn is_chanel_growing(&self, ch: &Chanels) -> bool {
+        self.chanel(ch) > 0.5
+    }
+}
+
+impl From<Cell> for WPoint {
+    fn from(cell: Cell) -> WPoint {
         let Cell {
             at:_,
             color: [r, g, b, a],
-            top_left,
-        } = self;
-        let x = top_left[0];
-        let y = top_left[1];
+        } = cell;
 
         WPoint::new(
             f64::value_from(r).unwrap(),
             f64::value_from(g).unwrap(),
             f64::value_from(b).unwrap(),
             f64::value_from(a).unwrap(),
-            x,
-            y,
         )
     }
 }
 
-impl Into<CellRepresntation> for Cell {
-    fn into(self) -> CellRepresntation {
+impl From<Cell> for CellRepresntation {
+    fn from(cell: Cell) -> CellRepresntation {
         let Cell {
             at,
             color:_,
-            top_left:_,
-        } = self;
+        } = cell;
 
         (
-            self.into(),
+            cell.into(),
             at.0,
             at.1,
         )
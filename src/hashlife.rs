@@ -0,0 +1,486 @@
+use crate::cell::{Cell, DEAD, SUPER_NOVA};
+use crate::rules::LifeLike;
+use crate::world::World;
+use std::collections::HashMap;
+
+type NodeId = usize;
+
+const DEAD_LEAF: NodeId = 0;
+const ALIVE_LEAF: NodeId = 1;
+// past this many nodes the ones the root no longer reaches are dropped
+const MAX_NODES: usize = 1 << 22;
+// the smallest root, a 4x4 node is the base case of the successor
+const MIN_LEVEL: u32 = 3;
+// a step grows the root to step_log + 3 levels, past this its side overflows an i64
+pub const MAX_STEP_LOG: u32 = 60;
+
+// a square of 2^level cells made of four quadrants, leaves are single cells
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct Node {
+    level: u32,
+    nw: NodeId,
+    ne: NodeId,
+    sw: NodeId,
+    se: NodeId,
+    population: u64,
+}
+
+// Gosper's HashLife, a memoized quadtree stepping binary Moore rules
+// by powers of two generations.
+// The plane is unbounded, export keeps what falls inside the world.
+pub struct HashLife {
+    birth: [bool; 9],
+    survival: [bool; 9],
+    nodes: Vec<Node>,
+    index: HashMap<[NodeId; 4], NodeId>,
+    empty: Vec<NodeId>,
+    results: HashMap<(NodeId, u32), NodeId>,
+    root: NodeId,
+    // where the root's top left cell sits in world rows and cols
+    origin: (i64, i64),
+    step_log: u32,
+    generation: u64,
+}
+
+impl HashLife {
+    // None for rules giving birth on empty space, the plane would fill up
+    pub fn new(rule: &LifeLike) -> Option<Self> {
+        if rule.is_born(0) {
+            return None;
+        }
+
+        let mut birth = [false; 9];
+        let mut survival = [false; 9];
+        for n in 0..9 {
+            birth[n] = rule.is_born(n);
+            survival[n] = rule.survives(n);
+        }
+
+        let leaf = |population| Node {
+            level: 0,
+            nw: DEAD_LEAF,
+            ne: DEAD_LEAF,
+            sw: DEAD_LEAF,
+            se: DEAD_LEAF,
+            population,
+        };
+
+        let mut hashlife = Self {
+            birth,
+            survival,
+            nodes: vec![leaf(0), leaf(1)],
+            index: HashMap::new(),
+            empty: vec![DEAD_LEAF],
+            results: HashMap::new(),
+            root: DEAD_LEAF,
+            origin: (0, 0),
+            step_log: 0,
+            generation: 0,
+        };
+        hashlife.root = hashlife.empty(MIN_LEVEL);
+
+        Some(hashlife)
+    }
+
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    pub fn population(&self) -> u64 {
+        self.nodes[self.root].population
+    }
+
+    // every step advances 2^step_log generations
+    pub fn step_log(&self) -> u32 {
+        self.step_log
+    }
+
+    pub fn set_step_log(&mut self, step_log: u32) {
+        self.step_log = step_log.min(MAX_STEP_LOG);
+    }
+
+    pub fn double_step(&mut self) {
+        self.set_step_log(self.step_log + 1);
+    }
+
+    pub fn halve_step(&mut self) {
+        self.step_log = self.step_log.saturating_sub(1);
+    }
+
+    // replaces the pattern with the live cells of the world
+    pub fn import(&mut self, world: &World) {
+        let (rows, cols) = (world.rows(), world.cols());
+        let mut alive = vec![false; rows * cols];
+        for cell in world.get_cells() {
+            alive[cell.at.0 * cols + cell.at.1] = cell.is_alive();
+        }
+
+        let mut level = MIN_LEVEL;
+        while (1 << level) < rows.max(cols) {
+            level += 1;
+        }
+
+        self.root = self.build(level, (0, 0), (rows, cols), &alive);
+        self.origin = (0, 0);
+        self.generation = 0;
+    }
+
+    fn build(
+        &mut self,
+        level: u32,
+        (row, col): (usize, usize),
+        (rows, cols): (usize, usize),
+        alive: &[bool],
+    ) -> NodeId {
+        if row >= rows || col >= cols {
+            return self.empty(level);
+        }
+        if level == 0 {
+            return if alive[row * cols + col] {
+                ALIVE_LEAF
+            } else {
+                DEAD_LEAF
+            };
+        }
+
+        let half = 1 << (level - 1);
+        let size = (rows, cols);
+        let nw = self.build(level - 1, (row, col), size, alive);
+        let ne = self.build(level - 1, (row, col + half), size, alive);
+        let sw = self.build(level - 1, (row + half, col), size, alive);
+        let se = self.build(level - 1, (row + half, col + half), size, alive);
+        self.join(nw, ne, sw, se)
+    }
+
+    // writes the cells of the world that changed, keeping colors of survivors
+    pub fn export(&self, world: &mut World) {
+        for cell in world.get_cells() {
+            let (row, col) = cell.at;
+            let alive = self.get(row as i64, col as i64);
+            if alive != cell.is_alive() {
+                world.write(Cell {
+                    color: if alive { SUPER_NOVA } else { DEAD },
+                    ..cell
+                });
+            }
+        }
+    }
+
+    pub fn get(&self, row: i64, col: i64) -> bool {
+        let (mut row, mut col) = (row - self.origin.0, col - self.origin.1);
+        let mut node = self.nodes[self.root];
+        let size = 1i64 << node.level;
+        if row < 0 || col < 0 || row >= size || col >= size {
+            return false;
+        }
+
+        while node.level > 0 {
+            if node.population == 0 {
+                return false;
+            }
+            let half = 1i64 << (node.level - 1);
+            let quadrant = match (row >= half, col >= half) {
+                (false, false) => node.nw,
+                (false, true) => node.ne,
+                (true, false) => node.sw,
+                (true, true) => node.se,
+            };
+            row %= half;
+            col %= half;
+            node = self.nodes[quadrant];
+        }
+
+        node.population > 0
+    }
+
+    // advances 2^step_log generations
+    pub fn step(&mut self) {
+        while self.level() > MIN_LEVEL && self.is_centered() {
+            let shift = 1i64 << (self.level() - 2);
+            self.root = self.centre(self.root);
+            self.origin = (self.origin.0 + shift, self.origin.1 + shift);
+        }
+        // room for the pattern to spread at the speed of light over the step
+        while self.level() < self.step_log + 2 || !self.is_centered() {
+            self.expand();
+        }
+        self.expand();
+
+        let shift = 1i64 << (self.level() - 2);
+        self.root = self.successor(self.root, self.step_log);
+        self.origin = (self.origin.0 + shift, self.origin.1 + shift);
+        self.generation += 1 << self.step_log;
+
+        if self.nodes.len() > MAX_NODES {
+            self.compact();
+        }
+    }
+
+    fn level(&self) -> u32 {
+        self.nodes[self.root].level
+    }
+
+    // the whole pattern lies in the middle half of the root
+    fn is_centered(&mut self) -> bool {
+        let centre = self.centre(self.root);
+        self.nodes[centre].population == self.population()
+    }
+
+    fn expand(&mut self) {
+        let Node {
+            level, nw, ne, sw, se, ..
+        } = self.nodes[self.root];
+        let e = self.empty(level - 1);
+
+        let nw = self.join(e, e, e, nw);
+        let ne = self.join(e, e, ne, e);
+        let sw = self.join(e, sw, e, e);
+        let se = self.join(se, e, e, e);
+        self.root = self.join(nw, ne, sw, se);
+
+        let shift = 1i64 << (level - 1);
+        self.origin = (self.origin.0 - shift, self.origin.1 - shift);
+    }
+
+    fn join(&mut self, nw: NodeId, ne: NodeId, sw: NodeId, se: NodeId) -> NodeId {
+        let key = [nw, ne, sw, se];
+        if let Some(&id) = self.index.get(&key) {
+            return id;
+        }
+
+        let population = key.iter().map(|&id| self.nodes[id].population).sum();
+        let id = self.nodes.len();
+        self.nodes.push(Node {
+            level: self.nodes[nw].level + 1,
+            nw,
+            ne,
+            sw,
+            se,
+            population,
+        });
+        self.index.insert(key, id);
+        id
+    }
+
+    fn empty(&mut self, level: u32) -> NodeId {
+        while self.empty.len() <= level as usize {
+            let e = *self.empty.last().unwrap();
+            let next = self.join(e, e, e, e);
+            self.empty.push(next);
+        }
+        self.empty[level as usize]
+    }
+
+    fn centre(&mut self, id: NodeId) -> NodeId {
+        let Node { nw, ne, sw, se, .. } = self.nodes[id];
+        let (nw, ne, sw, se) = (self.nodes[nw], self.nodes[ne], self.nodes[sw], self.nodes[se]);
+        self.join(nw.se, ne.sw, sw.ne, se.nw)
+    }
+
+    // the node straddling two side by side nodes
+    fn horizontal(&mut self, w: NodeId, e: NodeId) -> NodeId {
+        let (w, e) = (self.nodes[w], self.nodes[e]);
+        self.join(w.ne, e.nw, w.se, e.sw)
+    }
+
+    // the node straddling two stacked nodes
+    fn vertical(&mut self, n: NodeId, s: NodeId) -> NodeId {
+        let (n, s) = (self.nodes[n], self.nodes[s]);
+        self.join(n.sw, n.se, s.nw, s.ne)
+    }
+
+    // the middle 2x2 of a 4x4 node one generation later
+    fn base(&mut self, id: NodeId) -> NodeId {
+        let mut grid = [[false; 4]; 4];
+        for (row, line) in grid.iter_mut().enumerate() {
+            for (col, alive) in line.iter_mut().enumerate() {
+                *alive = self.cell_of(id, row, col);
+            }
+        }
+
+        let mut next = [DEAD_LEAF; 4];
+        for (i, (row, col)) in [(1, 1), (1, 2), (2, 1), (2, 2)].iter().enumerate() {
+            let mut neighbors = 0;
+            for d_row in 0..3 {
+                for d_col in 0..3 {
+                    if (d_row, d_col) != (1, 1) && grid[row + d_row - 1][col + d_col - 1] {
+                        neighbors += 1;
+                    }
+                }
+            }
+            let alive = if grid[*row][*col] {
+                self.survival[neighbors]
+            } else {
+                self.birth[neighbors]
+            };
+            if alive {
+                next[i] = ALIVE_LEAF;
+            }
+        }
+
+        self.join(next[0], next[1], next[2], next[3])
+    }
+
+    fn cell_of(&self, id: NodeId, row: usize, col: usize) -> bool {
+        let node = self.nodes[id];
+        if node.level == 0 {
+            return node.population > 0;
+        }
+        let half = 1 << (node.level - 1);
+        let quadrant = match (row >= half, col >= half) {
+            (false, false) => node.nw,
+            (false, true) => node.ne,
+            (true, false) => node.sw,
+            (true, true) => node.se,
+        };
+        self.cell_of(quadrant, row % half, col % half)
+    }
+
+    // the middle half of a node 2^step_log generations later, step_log is at most level - 2
+    fn successor(&mut self, id: NodeId, step_log: u32) -> NodeId {
+        let node = self.nodes[id];
+        if node.population == 0 {
+            return self.empty(node.level - 1);
+        }
+        let step_log = step_log.min(node.level - 2);
+        if let Some(&result) = self.results.get(&(id, step_log)) {
+            return result;
+        }
+
+        let result = if node.level == 2 {
+            self.base(id)
+        } else {
+            let Node { nw, ne, sw, se, .. } = node;
+            let full_speed = step_log == node.level - 2;
+
+            let parts = [
+                nw,
+                self.horizontal(nw, ne),
+                ne,
+                self.vertical(nw, sw),
+                self.centre(id),
+                self.vertical(ne, se),
+                sw,
+                self.horizontal(sw, se),
+                se,
+            ];
+            let mut c = [DEAD_LEAF; 9];
+            for (c, &part) in c.iter_mut().zip(parts.iter()) {
+                *c = if full_speed {
+                    self.successor(part, step_log - 1)
+                } else {
+                    self.centre(part)
+                };
+            }
+
+            let rest = if full_speed { step_log - 1 } else { step_log };
+            let quadrants = [
+                self.join(c[0], c[1], c[3], c[4]),
+                self.join(c[1], c[2], c[4], c[5]),
+                self.join(c[3], c[4], c[6], c[7]),
+                self.join(c[4], c[5], c[7], c[8]),
+            ];
+            let mut r = [DEAD_LEAF; 4];
+            for (r, &quadrant) in r.iter_mut().zip(quadrants.iter()) {
+                *r = self.successor(quadrant, rest);
+            }
+            self.join(r[0], r[1], r[2], r[3])
+        };
+
+        self.results.insert((id, step_log), result);
+        result
+    }
+
+    // rebuilds the tables with the nodes the root still reaches
+    fn compact(&mut self) {
+        let nodes = std::mem::take(&mut self.nodes);
+        self.nodes = nodes[..=ALIVE_LEAF].to_vec();
+        self.index.clear();
+        self.results.clear();
+        self.empty = vec![DEAD_LEAF];
+
+        let mut moved = HashMap::new();
+        self.root = self.copy(&nodes, self.root, &mut moved);
+    }
+
+    fn copy(&mut self, nodes: &[Node], id: NodeId, moved: &mut HashMap<NodeId, NodeId>) -> NodeId {
+        if id <= ALIVE_LEAF {
+            return id;
+        }
+        if let Some(&new) = moved.get(&id) {
+            return new;
+        }
+
+        let Node { nw, ne, sw, se, .. } = nodes[id];
+        let nw = self.copy(nodes, nw, moved);
+        let ne = self.copy(nodes, ne, moved);
+        let sw = self.copy(nodes, sw, moved);
+        let se = self.copy(nodes, se, moved);
+        let new = self.join(nw, ne, sw, se);
+        moved.insert(id, new);
+        new
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::boundary::Boundary;
+
+    fn world_with(rows: usize, cols: usize, alive: &[(usize, usize)]) -> World {
+        let mut world = World::new(rows, cols);
+        world.set_boundary(Boundary::Dead);
+        for &(row, col) in alive {
+            let cell = world.find_cell_at(row, col).unwrap();
+            world.write(Cell { color: SUPER_NOVA, ..cell });
+        }
+        world
+    }
+
+    fn alive(world: &World) -> Vec<(usize, usize)> {
+        world
+            .get_cells()
+            .iter()
+            .filter(|cell| cell.is_alive())
+            .map(|cell| cell.at)
+            .collect()
+    }
+
+    #[test]
+    fn matches_dense_steps() {
+        let rule = LifeLike::conway();
+        // far enough from the dead border that the plane and the world agree
+        let r_pentomino = [(30, 31), (30, 32), (31, 30), (31, 31), (32, 31)];
+
+        for step_log in 0..6 {
+            let mut dense = world_with(64, 64, &r_pentomino);
+            for _ in 0..1 << step_log {
+                dense.step(&rule);
+            }
+
+            let mut world = world_with(64, 64, &r_pentomino);
+            let mut hashlife = HashLife::new(&rule).unwrap();
+            hashlife.import(&world);
+            hashlife.set_step_log(step_log);
+            hashlife.step();
+            hashlife.export(&mut world);
+
+            assert_eq!(hashlife.generation(), 1 << step_log);
+            assert_eq!(alive(&world), alive(&dense), "2^{} generations", step_log);
+        }
+    }
+
+    #[test]
+    fn blinker_survives_the_largest_jump() {
+        let blinker = [(4, 3), (4, 4), (4, 5)];
+        let mut world = world_with(8, 8, &blinker);
+        let mut hashlife = HashLife::new(&LifeLike::conway()).unwrap();
+        hashlife.import(&world);
+        hashlife.set_step_log(u32::MAX);
+        assert_eq!(hashlife.step_log(), MAX_STEP_LOG);
+
+        hashlife.step();
+        hashlife.export(&mut world);
+        assert_eq!(alive(&world), alive(&world_with(8, 8, &blinker)));
+    }
+}
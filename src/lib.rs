@@ -5,9 +5,21 @@ extern crate graphics;
 extern crate opengl_graphics;
 extern crate piston;
 extern crate palette;
+extern crate rayon;
 
 
+pub mod ant;
 pub mod app;
+pub mod bit_grid;
+pub mod boundary;
 pub mod world;
 pub mod cell;
+pub mod hashlife;
+pub mod neighborhood;
+pub mod random;
+pub mod rules;
+pub mod schedule;
+pub mod sparse_world;
+pub mod topology;
+pub mod view;
 pub mod world_controller;
\ No newline at end of file
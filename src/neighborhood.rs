@@ -0,0 +1,272 @@
+use crate::topology::Topology;
+use std::collections::HashSet;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Shape {
+    Moore,
+    VonNeumann,
+    Circular,
+    Cross,
+    Hexagonal,
+}
+
+impl Shape {
+    pub fn contains(&self, radius: usize, (row, col): (isize, isize)) -> bool {
+        let radius = radius as isize;
+        if row.abs() > radius || col.abs() > radius {
+            return false;
+        }
+        match self {
+            Shape::Moore => true,
+            Shape::VonNeumann => row.abs() + col.abs() <= radius,
+            Shape::Circular => row * row + col * col <= radius * radius + radius,
+            Shape::Cross => row == 0 || col == 0,
+            // hexagons on a square grid, skewed along the main diagonal
+            Shape::Hexagonal => (row - col).abs() <= radius,
+        }
+    }
+
+    // The cells a triangle pointing up reaches in radius steps, over
+    // shared edges for von Neumann and cross, over shared corners otherwise.
+    fn triangles(&self, radius: usize) -> Vec<(isize, isize, f64)> {
+        let over_edges = matches!(self, Shape::VonNeumann | Shape::Cross);
+        let mut reached = HashSet::new();
+        let mut front = vec![(0_isize, 0_isize)];
+        reached.insert((0, 0));
+
+        for _ in 0..radius {
+            let mut next = Vec::new();
+            for &(row, col) in &front {
+                let up = (row + col) % 2 == 0;
+                let toward = if up { 1 } else { -1 };
+                let adjacent: Vec<(isize, isize)> = if over_edges {
+                    vec![(0, -1), (0, 1), (toward, 0)]
+                } else {
+                    let mut adjacent = vec![(0, -2), (0, -1), (0, 1), (0, 2)];
+                    adjacent.extend((-1..=1).map(|c| (-toward, c)));
+                    adjacent.extend((-2..=2).map(|c| (toward, c)));
+                    adjacent
+                };
+                for (d_row, d_col) in adjacent {
+                    let at = (row + d_row, col + d_col);
+                    if reached.insert(at) {
+                        next.push(at);
+                    }
+                }
+            }
+            front = next;
+        }
+
+        let mut weights: Vec<(isize, isize, f64)> = reached
+            .into_iter()
+            .filter(|at| *at != (0, 0))
+            .map(|(row, col)| (row, col, 1.0))
+            .collect();
+        weights.sort_by_key(|&(row, col, _)| (col, row));
+        weights
+    }
+}
+
+// Weights of the cells around a center at (0, 0), as (row, col) offsets.
+// Shapes leave the center out, custom masks may weigh it too.
+// On triangles the offsets are those of a cell pointing up,
+// cells pointing down see them upside down.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Neighborhood {
+    shape: Option<Shape>,
+    radius: usize,
+    weights: Vec<(isize, isize, f64)>,
+    triangles: Vec<(isize, isize, f64)>,
+}
+
+impl Neighborhood {
+    pub fn new(shape: Shape, radius: usize) -> Self {
+        let mut neighborhood = Self::from_fn(radius, |row, col| {
+            if (row, col) != (0, 0) && shape.contains(radius, (row, col)) {
+                1.0
+            } else {
+                0.0
+            }
+        });
+        neighborhood.shape = Some(shape);
+        neighborhood.triangles = shape.triangles(radius);
+        neighborhood
+    }
+
+    pub fn moore(radius: usize) -> Self {
+        Self::new(Shape::Moore, radius)
+    }
+
+    pub fn von_neumann(radius: usize) -> Self {
+        Self::new(Shape::VonNeumann, radius)
+    }
+
+    pub fn circular(radius: usize) -> Self {
+        Self::new(Shape::Circular, radius)
+    }
+
+    pub fn cross(radius: usize) -> Self {
+        Self::new(Shape::Cross, radius)
+    }
+
+    pub fn hexagonal(radius: usize) -> Self {
+        Self::new(Shape::Hexagonal, radius)
+    }
+
+    pub fn from_fn<F>(radius: usize, weight: F) -> Self
+    where
+        F: Fn(isize, isize) -> f64,
+    {
+        let span = radius as isize;
+        let mut weights = Vec::new();
+
+        for col in -span..=span {
+            for row in -span..=span {
+                let w = weight(row, col);
+                if w != 0.0 {
+                    weights.push((row, col, w));
+                }
+            }
+        }
+
+        Self {
+            shape: None,
+            radius,
+            triangles: weights.clone(),
+            weights,
+        }
+    }
+
+    // a row-major (2r + 1) x (2r + 1) mask, centered on the cell
+    pub fn weighted(radius: usize, mask: &[f64]) -> Option<Self> {
+        let side = radius * 2 + 1;
+        if mask.len() != side * side {
+            return None;
+        }
+
+        let span = radius as isize;
+        Some(Self::from_fn(radius, |row, col| {
+            mask[((row + span) as usize) * side + (col + span) as usize]
+        }))
+    }
+
+    pub fn shape(&self) -> Option<Shape> {
+        self.shape
+    }
+
+    pub fn radius(&self) -> usize {
+        self.radius
+    }
+
+    // custom masks keep their own radius
+    pub fn with_radius(&self, radius: usize) -> Self {
+        match self.shape {
+            Some(shape) => Self::new(shape, radius),
+            None => self.clone(),
+        }
+    }
+
+    pub fn weight(&self, row: isize, col: isize) -> f64 {
+        self.weights
+            .iter()
+            .find(|(r, c, _)| (*r, *c) == (row, col))
+            .map_or(0.0, |(_, _, w)| *w)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &(isize, isize, f64)> {
+        self.weights.iter()
+    }
+
+    pub fn offsets(&self, topology: Topology) -> &[(isize, isize, f64)] {
+        match topology {
+            Topology::Triangular => &self.triangles,
+            _ => &self.weights,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.weights.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.weights.is_empty()
+    }
+
+    pub fn total_weight(&self) -> f64 {
+        self.weights.iter().map(|(_, _, w)| w).sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn shapes_count_their_neighbors() {
+        assert_eq!(Neighborhood::von_neumann(1).len(), 4);
+        assert_eq!(Neighborhood::moore(1).len(), 8);
+        assert_eq!(Neighborhood::cross(1).len(), 4);
+        assert_eq!(Neighborhood::hexagonal(1).len(), 6);
+        assert_eq!(Neighborhood::moore(2).len(), 24);
+        assert_eq!(Neighborhood::von_neumann(2).len(), 12);
+        assert_eq!(Neighborhood::circular(2).len(), 20);
+        assert_eq!(Neighborhood::hexagonal(2).len(), 18);
+    }
+
+    #[test]
+    fn hexagonal_lattice_keeps_six_of_moore() {
+        let moore = Neighborhood::moore(1);
+        // the lattice shifts odd rows, both must see six neighbors
+        for &at in [(2, 2), (3, 2)].iter() {
+            let seen = moore
+                .offsets(Topology::Hexagonal)
+                .iter()
+                .filter_map(|&(row, col, _)| Topology::Hexagonal.offset(at, (row, col), 1))
+                .collect::<HashSet<_>>();
+            assert_eq!(seen.len(), 6, "{:?}", at);
+        }
+    }
+
+    #[test]
+    fn triangles_reach_over_edges_or_corners() {
+        assert_eq!(Neighborhood::moore(1).offsets(Topology::Triangular).len(), 12);
+        assert_eq!(Neighborhood::von_neumann(1).offsets(Topology::Triangular).len(), 3);
+        // two steps over edges reach the three edge neighbors and six more triangles
+        assert_eq!(Neighborhood::von_neumann(2).offsets(Topology::Triangular).len(), 9);
+        // a triangle pointing up has its third edge neighbor below it
+        assert_eq!(
+            Neighborhood::von_neumann(1).offsets(Topology::Triangular),
+            &[(0, -1, 1.0), (1, 0, 1.0), (0, 1, 1.0)]
+        );
+    }
+
+    #[test]
+    fn from_fn_keeps_nonzero_weights() {
+        let neighborhood = Neighborhood::from_fn(1, |row, col| (row * 3 + col) as f64);
+        assert_eq!(neighborhood.len(), 8);
+        assert_eq!(neighborhood.weight(1, 1), 4.0);
+        assert_eq!(neighborhood.weight(0, 0), 0.0);
+        assert_eq!(neighborhood.total_weight(), 0.0);
+        assert_eq!(neighborhood.shape(), None);
+    }
+
+    #[test]
+    fn weighted_reads_a_row_major_mask() {
+        let mask = [0.0, 1.0, 0.0, 0.5, 2.0, 0.5, 0.0, 1.0, 0.0];
+        let neighborhood = Neighborhood::weighted(1, &mask).unwrap();
+        assert_eq!(neighborhood.len(), 5);
+        assert_eq!(neighborhood.weight(-1, 0), 1.0);
+        assert_eq!(neighborhood.weight(0, -1), 0.5);
+        assert_eq!(neighborhood.weight(0, 0), 2.0);
+        assert_eq!(neighborhood.total_weight(), 5.0);
+        // custom masks keep their radius
+        assert_eq!(neighborhood.with_radius(3), neighborhood);
+    }
+
+    #[test]
+    fn weighted_rejects_a_mask_of_the_wrong_length() {
+        assert_eq!(Neighborhood::weighted(1, &[1.0; 8]), None);
+        assert_eq!(Neighborhood::weighted(1, &[1.0; 25]), None);
+        assert_eq!(Neighborhood::weighted(2, &[1.0; 25]).map(|n| n.len()), Some(25));
+    }
+}
@@ -0,0 +1,46 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const GOLDEN_GAMMA: u64 = 0x9e37_79b9_7f4a_7c15;
+
+// the splitmix64 finalizer
+fn mix(mut z: u64) -> u64 {
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+// A counter based generator, the same seed and keys always give the same number,
+// whatever order the cells are stepped in.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Rng {
+    seed: u64,
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Self { seed }
+    }
+
+    pub fn from_time() -> Self {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_nanos() as u64)
+            .unwrap_or(0);
+        Self::new(mix(nanos) % 1_000_000)
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    pub fn hash(&self, keys: &[u64]) -> u64 {
+        keys.iter().fold(mix(self.seed ^ GOLDEN_GAMMA), |hash, key| {
+            mix(hash.wrapping_add(GOLDEN_GAMMA) ^ key)
+        })
+    }
+
+    // uniform in [0, 1)
+    pub fn sample(&self, keys: &[u64]) -> f64 {
+        (self.hash(keys) >> 11) as f64 / (1u64 << 53) as f64
+    }
+}
@@ -0,0 +1,162 @@
+use crate::cell::Cell;
+use crate::neighborhood::Neighborhood;
+use graphics::types::Color;
+use crate::world::World;
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
+pub mod cellulose;
+pub mod elementary;
+pub mod generations;
+pub mod immigration;
+pub mod larger_than_life;
+pub mod lenia;
+pub mod life_like;
+pub mod margolus;
+pub mod rgb_life;
+pub mod smooth_life;
+pub mod stochastic;
+pub mod wireworld;
+
+pub use cellulose::Cellulose;
+pub use elementary::Elementary;
+pub use generations::Generations;
+pub use immigration::Immigration;
+pub use larger_than_life::LargerThanLife;
+pub use lenia::Lenia;
+pub use life_like::LifeLike;
+pub use margolus::Margolus;
+pub use rgb_life::RgbLife;
+pub use smooth_life::SmoothLife;
+pub use stochastic::Stochastic;
+pub use wireworld::Wireworld;
+
+// the name of the preset with this rulestring, or the rulestring itself
+pub fn preset_name(presets: &[(&str, &str)], rulestring: &str) -> String {
+    preset_name_by(presets, rulestring, |preset| preset == rulestring)
+}
+
+// the name of the first preset that matches, for rules with several ways to write one
+pub fn preset_name_by<F>(presets: &[(&str, &str)], rulestring: &str, matches: F) -> String
+where
+    F: Fn(&str) -> bool,
+{
+    presets
+        .iter()
+        .find(|(_, preset)| matches(preset))
+        .map(|(name, _)| String::from(*name))
+        .unwrap_or_else(|| String::from(rulestring))
+}
+
+// presets are known to parse
+pub fn parse_presets<T>(presets: &[(&str, &str)]) -> Vec<T>
+where
+    T: FromStr,
+    T::Err: fmt::Debug,
+{
+    presets
+        .iter()
+        .map(|(_, rulestring)| rulestring.parse().unwrap())
+        .collect()
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum RuleParseError {
+    Empty,
+    MissingSection(char),
+    DuplicateSection(char),
+    UnexpectedChar(char),
+    CountOutOfRange(usize),
+    InvertedRange(usize, usize),
+    StatesOutOfRange(usize),
+    RadiusOutOfRange(usize),
+    BlockOutOfRange(usize),
+    TableLength(usize),
+}
+
+impl fmt::Display for RuleParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RuleParseError::Empty => write!(f, "empty rulestring"),
+            RuleParseError::MissingSection(s) => write!(f, "missing '{}' section", s),
+            RuleParseError::DuplicateSection(s) => write!(f, "'{}' section given twice", s),
+            RuleParseError::UnexpectedChar(c) => write!(f, "unexpected character '{}'", c),
+            RuleParseError::CountOutOfRange(n) => {
+                write!(f, "neighbor count {} is out of range", n)
+            }
+            RuleParseError::InvertedRange(min, max) => {
+                write!(f, "range {}..{} ends before it starts", min, max)
+            }
+            RuleParseError::StatesOutOfRange(n) => {
+                write!(f, "{} states is out of range, at least 2 are needed", n)
+            }
+            RuleParseError::RadiusOutOfRange(n) => write!(f, "radius {} is out of range", n),
+            RuleParseError::BlockOutOfRange(n) => write!(f, "block {} is out of range", n),
+            RuleParseError::TableLength(n) => {
+                write!(f, "rule table has {} entries, 16 are needed", n)
+            }
+        }
+    }
+}
+
+impl Error for RuleParseError {}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct RuleMetadata {
+    pub description: Option<String>,
+    pub author: Option<String>,
+    pub rulestring: Option<String>,
+}
+
+// rules are shared by the threads stepping a world
+pub trait Rule: Send + Sync {
+    fn name(&self) -> &str;
+
+    fn neighborhood(&self) -> &Neighborhood;
+
+    fn radius(&self) -> usize {
+        self.neighborhood().radius()
+    }
+
+    // rules with a fixed neighborhood ignore it
+    fn set_radius(&mut self, _radius: usize) {}
+
+    fn step(&self, world: &World, cell: Cell) -> Option<Cell>;
+
+    // the color a cell is drawn with
+    fn paint(&self, cell: &Cell) -> Color {
+        cell.color
+    }
+
+    // named colors to draw with, rules without any use the hue cycling cursor
+    fn brushes(&self) -> &[(&'static str, Color)] {
+        &[]
+    }
+
+    // a cell whose neighborhood did not change keeps its state, so synchronous steps
+    // skip the quiet parts of a world, not so for rules drawing on the generation or chance
+    fn time_invariant(&self) -> bool {
+        true
+    }
+
+    // rules updating groups of cells together need all of them to read the same
+    // generation, worlds step them synchronously whatever their schedule
+    fn synchronous_only(&self) -> bool {
+        false
+    }
+
+    // 1D rules evolve the bottom row and scroll its history up, infinite worlds only widen
+    fn one_dimensional(&self) -> bool {
+        false
+    }
+
+    // binary Moore rules can be fast-forwarded by the HashLife engine
+    fn as_life_like(&self) -> Option<&LifeLike> {
+        None
+    }
+
+    fn metadata(&self) -> Option<RuleMetadata> {
+        None
+    }
+}
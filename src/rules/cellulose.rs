@@ -0,0 +1,83 @@
+use crate::cell::{Cell, DEAD, SUPER_NOVA};
+use crate::neighborhood::Neighborhood;
+use crate::rules::{Rule, RuleMetadata};
+use crate::world::World;
+
+pub struct Cellulose {
+    neighborhood: Neighborhood,
+}
+
+impl Cellulose {
+    pub fn new(radius: usize) -> Self {
+        Self {
+            neighborhood: Neighborhood::moore(radius),
+        }
+    }
+}
+
+impl Default for Cellulose {
+    fn default() -> Self {
+        Self::new(1)
+    }
+}
+
+impl Rule for Cellulose {
+    fn name(&self) -> &str {
+        "Cellulose"
+    }
+
+    fn neighborhood(&self) -> &Neighborhood {
+        &self.neighborhood
+    }
+
+    fn set_radius(&mut self, radius: usize) {
+        self.neighborhood = Neighborhood::moore(radius.max(1));
+    }
+
+    fn step(&self, world: &World, t_cell: Cell) -> Option<Cell> {
+        let radius = self.radius();
+        let alive = t_cell.is_alive();
+        let neighbors_alive = world.count_alive(t_cell.at, &self.neighborhood);
+
+        if neighbors_alive >= radius * 4 {
+            if alive {
+                Some(Cell {
+                    color: DEAD,
+                    ..t_cell
+                })
+            } else {
+                None
+            }
+        } else if neighbors_alive >= radius * 3 {
+            if !alive {
+                Some(Cell {
+                    color: SUPER_NOVA,
+                    ..t_cell
+                })
+            } else {
+                None
+            }
+        } else if neighbors_alive < radius * 2 {
+            if alive {
+                Some(Cell {
+                    color: DEAD,
+                    ..t_cell
+                })
+            } else {
+                None
+            }
+        } else {
+            None
+        }
+    }
+
+    fn metadata(&self) -> Option<RuleMetadata> {
+        Some(RuleMetadata {
+            description: Some(String::from(
+                "thresholds grow with the radius: dies from 4r, born from 3r, starves under 2r",
+            )),
+            author: Some(String::from("anvlkv")),
+            rulestring: None,
+        })
+    }
+}
@@ -0,0 +1,299 @@
+use crate::cell::{Cell, DEAD, SUPER_NOVA};
+use crate::neighborhood::Neighborhood;
+use crate::rules::{parse_presets, preset_name, Rule, RuleMetadata, RuleParseError};
+use crate::world::World;
+use graphics::types::Color;
+use std::fmt;
+use std::str::FromStr;
+
+pub const PRESETS: [(&str, &str); 5] = [
+    ("Rule 30", "W30"),
+    ("Rule 90", "W90"),
+    ("Rule 110", "W110"),
+    ("Rule 184", "W184"),
+    ("Code 1599", "K3,R1,T1599"),
+];
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Code {
+    Wolfram(u8),
+    Totalistic { colors: usize, code: u64 },
+}
+
+// The bottom row evolves, the rows above scroll up one row a generation
+// and keep its history as a space-time diagram.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Elementary {
+    name: String,
+    code: Code,
+    neighborhood: Neighborhood,
+}
+
+impl Elementary {
+    pub fn wolfram(rule: u8) -> Self {
+        Self::build(Code::Wolfram(rule), 1)
+    }
+
+    pub fn totalistic(colors: usize, radius: usize, code: u64) -> Result<Self, RuleParseError> {
+        if colors < 2 {
+            return Err(RuleParseError::StatesOutOfRange(colors));
+        }
+        if radius == 0 {
+            return Err(RuleParseError::RadiusOutOfRange(radius));
+        }
+
+        let sums = (radius * 2 + 1) * (colors - 1) + 1;
+        let codes = (colors as u64).checked_pow(sums as u32);
+        if codes.is_some_and(|codes| code >= codes) {
+            return Err(RuleParseError::CountOutOfRange(code as usize));
+        }
+
+        Ok(Self::build(Code::Totalistic { colors, code }, radius))
+    }
+
+    fn build(code: Code, radius: usize) -> Self {
+        let span = radius as isize;
+        let neighborhood = match code {
+            // weighs the left, middle and right cell into the bit of the rule
+            Code::Wolfram(_) => Neighborhood::from_fn(1, |row, col| {
+                if row == 0 {
+                    [4.0, 2.0, 1.0][(col + 1) as usize]
+                } else {
+                    0.0
+                }
+            }),
+            Code::Totalistic { .. } => Neighborhood::from_fn(radius, |row, col| {
+                if row == 0 && col.abs() <= span {
+                    1.0
+                } else {
+                    0.0
+                }
+            }),
+        };
+
+        let mut rule = Self {
+            name: String::new(),
+            code,
+            neighborhood,
+        };
+
+        rule.name = preset_name(&PRESETS, &rule.to_string());
+
+        rule
+    }
+
+    pub fn presets() -> Vec<Self> {
+        parse_presets(&PRESETS)
+    }
+
+    fn colors(&self) -> usize {
+        match self.code {
+            Code::Wolfram(_) => 2,
+            Code::Totalistic { colors, .. } => colors,
+        }
+    }
+
+    pub fn state_of(&self, cell: &Cell) -> usize {
+        if !cell.is_alive() {
+            return 0;
+        }
+        let [_, _, _, a] = cell.color;
+        let top = self.colors() - 1;
+        ((a * top as f32).round() as usize).clamp(1, top)
+    }
+
+    pub fn color_of(&self, state: usize) -> Color {
+        match state {
+            0 => DEAD,
+            s if s + 1 == self.colors() => SUPER_NOVA,
+            s => [1.0, 1.0, 1.0, s as f32 / (self.colors() - 1) as f32],
+        }
+    }
+
+    fn parse_number(digits: &str) -> Result<u64, RuleParseError> {
+        if let Some(c) = digits.chars().find(|c| !c.is_ascii_digit()) {
+            return Err(RuleParseError::UnexpectedChar(c));
+        }
+        digits
+            .parse()
+            .map_err(|_| RuleParseError::CountOutOfRange(usize::MAX))
+    }
+}
+
+impl FromStr for Elementary {
+    type Err = RuleParseError;
+
+    // "W110" for Wolfram codes, "K3,R1,T1599" for k-color totalistic codes
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if s.is_empty() {
+            return Err(RuleParseError::Empty);
+        }
+
+        let mut colors = None;
+        let mut radius = 1;
+        let mut code = None;
+        let mut wolfram = None;
+
+        for token in s.split(',').map(str::trim) {
+            let mut chars = token.chars();
+            let letter = chars
+                .next()
+                .ok_or(RuleParseError::UnexpectedChar(','))?
+                .to_ascii_uppercase();
+            let value = Self::parse_number(chars.as_str())?;
+
+            match letter {
+                'W' if value > 255 => return Err(RuleParseError::CountOutOfRange(value as usize)),
+                'W' => wolfram = Some(value as u8),
+                'K' => colors = Some(value as usize),
+                'R' => radius = value as usize,
+                'T' => code = Some(value),
+                c => return Err(RuleParseError::UnexpectedChar(c)),
+            }
+        }
+
+        match (wolfram, code) {
+            (Some(rule), None) => Ok(Self::wolfram(rule)),
+            (None, Some(code)) => Self::totalistic(colors.unwrap_or(2), radius, code),
+            (Some(_), Some(_)) => Err(RuleParseError::DuplicateSection('T')),
+            (None, None) => Err(RuleParseError::MissingSection('W')),
+        }
+    }
+}
+
+impl fmt::Display for Elementary {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.code {
+            Code::Wolfram(rule) => write!(f, "W{}", rule),
+            Code::Totalistic { colors, code } => {
+                write!(f, "K{},R{},T{}", colors, self.neighborhood.radius(), code)
+            }
+        }
+    }
+}
+
+impl Rule for Elementary {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn neighborhood(&self) -> &Neighborhood {
+        &self.neighborhood
+    }
+
+    fn step(&self, world: &World, t_cell: Cell) -> Option<Cell> {
+        let (row, col) = t_cell.at;
+
+        if row + 1 < world.rows() {
+            let below = world.find_cell_at(row + 1, col)?;
+            return if below.color == t_cell.color {
+                None
+            } else {
+                Some(Cell {
+                    color: below.color,
+                    ..t_cell
+                })
+            };
+        }
+
+        let next_state = match self.code {
+            Code::Wolfram(rule) => {
+                let bit = world.count_alive(t_cell.at, &self.neighborhood);
+                ((rule >> bit) & 1) as usize
+            }
+            Code::Totalistic { colors, code } => {
+                let sum = world
+                    .weighted_sum(t_cell.at, &self.neighborhood, |n| self.state_of(n) as f64)
+                    .round() as u32;
+                let digit = (colors as u64).checked_pow(sum).map_or(0, |place| code / place);
+                (digit % colors as u64) as usize
+            }
+        };
+
+        if next_state == self.state_of(&t_cell) {
+            None
+        } else {
+            Some(Cell {
+                color: self.color_of(next_state),
+                ..t_cell
+            })
+        }
+    }
+
+    fn one_dimensional(&self) -> bool {
+        true
+    }
+
+    fn metadata(&self) -> Option<RuleMetadata> {
+        Some(RuleMetadata {
+            description: Some(String::from(
+                "one dimensional rule on the bottom row, older generations scroll up",
+            )),
+            author: None,
+            rulestring: Some(self.to_string()),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::boundary::Boundary;
+
+    #[test]
+    fn presets_round_trip() {
+        for (name, rulestring) in PRESETS.iter() {
+            let rule: Elementary = rulestring.parse().unwrap();
+            assert_eq!(rule.to_string(), *rulestring);
+            assert_eq!(rule.name(), *name);
+        }
+    }
+
+    #[test]
+    fn parses_loose_forms() {
+        assert_eq!("w30".parse::<Elementary>().unwrap(), Elementary::wolfram(30));
+        let rule: Elementary = " T10 , R2 ".parse().unwrap();
+        assert_eq!(rule.to_string(), "K2,R2,T10");
+        assert_eq!(rule, Elementary::totalistic(2, 2, 10).unwrap());
+    }
+
+    #[test]
+    fn rejects_bad_rulestrings() {
+        let error = |s: &str| s.parse::<Elementary>().unwrap_err();
+        assert_eq!(error(""), RuleParseError::Empty);
+        assert_eq!(error("W256"), RuleParseError::CountOutOfRange(256));
+        assert_eq!(error("W3x"), RuleParseError::UnexpectedChar('x'));
+        assert_eq!(error("K3,R1"), RuleParseError::MissingSection('W'));
+        assert_eq!(error("W30,T5"), RuleParseError::DuplicateSection('T'));
+        assert_eq!(error("K1,T0"), RuleParseError::StatesOutOfRange(1));
+        assert_eq!(error("R0,T0"), RuleParseError::RadiusOutOfRange(0));
+        // 2 colors and radius 1 give 4 sums, so 16 codes
+        assert_eq!(error("K2,R1,T16"), RuleParseError::CountOutOfRange(16));
+        assert_eq!(error("W30,Q1"), RuleParseError::UnexpectedChar('Q'));
+    }
+
+    #[test]
+    fn infinite_worlds_widen_and_keep_the_history() {
+        let rule = Elementary::wolfram(90);
+        let mut world = World::new(12, 20);
+        world.set_boundary(Boundary::Infinite);
+        let seed = world.find_cell_at(11, 10).unwrap();
+        world.write(Cell { color: SUPER_NOVA, ..seed });
+
+        for _ in 0..11 {
+            world.step(&rule);
+        }
+        // the first row scrolled to the top and the pattern outgrew the sides
+        assert_eq!(world.rows(), 12);
+        assert!(world.cols() > 20);
+        let alive = |row: usize| {
+            (0..world.cols())
+                .filter(|&col| world.find_cell_at(row, col).unwrap().is_alive())
+                .count()
+        };
+        assert_eq!(alive(0), 1);
+        // Rule 90 from one cell draws Pascal's triangle mod 2, its row 11 = 0b1011 has 2^3 ones
+        assert_eq!(alive(11), 8);
+    }
+}
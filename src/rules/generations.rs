@@ -0,0 +1,218 @@
+use crate::cell::{Cell, DEAD, SUPER_NOVA};
+use crate::neighborhood::Neighborhood;
+use crate::rules::{parse_presets, preset_name, LifeLike, Rule, RuleMetadata, RuleParseError};
+use crate::world::World;
+use graphics::types::Color;
+use std::fmt;
+use std::str::FromStr;
+
+pub const PRESETS: [(&str, &str); 5] = [
+    ("Brian's Brain", "B2/S/C3"),
+    ("Star Wars", "B2/S345/C4"),
+    ("Frogs", "B34/S12/C3"),
+    ("Spirals", "B234/S2/C5"),
+    ("Fireworks", "B13/S2/C21"),
+];
+
+// State 0 is dead and state 1 is alive, states 2 to C-1 are refractory:
+// they keep the color of the cell that died and fade its alpha out.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Generations {
+    name: String,
+    life: LifeLike,
+    states: usize,
+}
+
+impl Generations {
+    pub fn new(life: LifeLike, states: usize) -> Result<Self, RuleParseError> {
+        if states < 2 {
+            return Err(RuleParseError::StatesOutOfRange(states));
+        }
+
+        let mut rule = Self {
+            name: String::new(),
+            life,
+            states,
+        };
+
+        rule.name = preset_name(&PRESETS, &rule.to_string());
+
+        Ok(rule)
+    }
+
+    pub fn brians_brain() -> Self {
+        "B2/S/C3".parse().unwrap()
+    }
+
+    pub fn presets() -> Vec<Self> {
+        parse_presets(&PRESETS)
+    }
+
+    pub fn states(&self) -> usize {
+        self.states
+    }
+
+    pub fn state_of(&self, cell: &Cell) -> usize {
+        decay_state(cell, self.states)
+    }
+
+    pub fn color_of(&self, color: Color, state: usize) -> Color {
+        decay_color(color, state, self.states)
+    }
+}
+
+pub fn decay_state(cell: &Cell, states: usize) -> usize {
+    if !cell.is_alive() {
+        return 0;
+    }
+
+    let [_, _, _, a] = cell.color;
+    let refractory = ((1.0 - a) * (states - 1) as f32).round() as usize;
+    (refractory + 1).min(states - 1)
+}
+
+pub fn decay_color(color: Color, state: usize, states: usize) -> Color {
+    match state {
+        0 => DEAD,
+        1 => color,
+        _ => {
+            let [r, g, b, _] = color;
+            let fade = (states - state) as f32 / (states - 1) as f32;
+            [r, g, b, fade]
+        }
+    }
+}
+
+impl FromStr for Generations {
+    type Err = RuleParseError;
+
+    // accepts "B2/S/C3", "B2S345C4" and the S/B/C form "345/2/4"
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if s.is_empty() {
+            return Err(RuleParseError::Empty);
+        }
+
+        let (life, states) = if s.starts_with(|c: char| c.is_ascii_alphabetic()) {
+            let at = s
+                .rfind(['C', 'c'])
+                .ok_or(RuleParseError::MissingSection('C'))?;
+            (s[..at].trim_end_matches('/'), &s[at + 1..])
+        } else {
+            let at = s.rfind('/').ok_or(RuleParseError::MissingSection('C'))?;
+            (&s[..at], &s[at + 1..])
+        };
+
+        if life.is_empty() {
+            return Err(RuleParseError::MissingSection('B'));
+        }
+        if states.is_empty() {
+            return Err(RuleParseError::MissingSection('C'));
+        }
+
+        if let Some(c) = states.chars().find(|c| !c.is_ascii_digit()) {
+            return Err(RuleParseError::UnexpectedChar(c));
+        }
+        let states = states
+            .parse()
+            .map_err(|_| RuleParseError::StatesOutOfRange(usize::MAX))?;
+
+        Self::new(life.parse()?, states)
+    }
+}
+
+impl fmt::Display for Generations {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}/C{}", self.life, self.states)
+    }
+}
+
+impl Rule for Generations {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn neighborhood(&self) -> &Neighborhood {
+        self.life.neighborhood()
+    }
+
+    fn step(&self, world: &World, t_cell: Cell) -> Option<Cell> {
+        let state = self.state_of(&t_cell);
+        let neighbors_alive = world
+            .weighted_sum(t_cell.at, self.neighborhood(), |n| {
+                if self.state_of(n) == 1 {
+                    1.0
+                } else {
+                    0.0
+                }
+            })
+            .round() as usize;
+
+        let next_state = match state {
+            0 if self.life.is_born(neighbors_alive) => 1,
+            0 => 0,
+            1 if self.life.survives(neighbors_alive) => 1,
+            s => (s + 1) % self.states,
+        };
+
+        if next_state == state {
+            None
+        } else if state == 0 {
+            Some(Cell {
+                color: SUPER_NOVA,
+                ..t_cell
+            })
+        } else {
+            Some(Cell {
+                color: self.color_of(t_cell.color, next_state),
+                ..t_cell
+            })
+        }
+    }
+
+    fn metadata(&self) -> Option<RuleMetadata> {
+        Some(RuleMetadata {
+            description: Some(format!(
+                "Life-like rule where dying cells fade through {} refractory states",
+                self.states - 2
+            )),
+            author: None,
+            rulestring: Some(self.to_string()),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn presets_round_trip() {
+        for (name, rulestring) in PRESETS.iter() {
+            let rule: Generations = rulestring.parse().unwrap();
+            assert_eq!(rule.to_string(), *rulestring);
+            assert_eq!(rule.name(), *name);
+        }
+    }
+
+    #[test]
+    fn parses_every_notation() {
+        for rulestring in ["B2/S/C3", "B2/SC3", "B2S/C3", "b2/s/c3", "/2/3"].iter() {
+            let rule: Generations = rulestring.parse().unwrap();
+            assert_eq!(rule, Generations::brians_brain(), "{}", rulestring);
+        }
+    }
+
+    #[test]
+    fn rejects_bad_rulestrings() {
+        let error = |s: &str| s.parse::<Generations>().unwrap_err();
+        assert_eq!(error(""), RuleParseError::Empty);
+        assert_eq!(error("B2/S"), RuleParseError::MissingSection('C'));
+        assert_eq!(error("B2/S/C"), RuleParseError::MissingSection('C'));
+        assert_eq!(error("B2/S/C3x"), RuleParseError::UnexpectedChar('x'));
+        assert_eq!(error("B2/S/C1"), RuleParseError::StatesOutOfRange(1));
+        assert_eq!(error("B29/S/C3"), RuleParseError::CountOutOfRange(9));
+        assert_eq!(error("C3"), RuleParseError::MissingSection('B'));
+        assert_eq!(error("/3"), RuleParseError::MissingSection('B'));
+    }
+}
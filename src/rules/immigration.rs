@@ -0,0 +1,208 @@
+use crate::cell::{Cell, DEAD};
+use crate::neighborhood::Neighborhood;
+use crate::rules::{LifeLike, Rule, RuleMetadata};
+use crate::world::World;
+use graphics::types::Color;
+use palette::{Hsv, LinSrgb};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Inheritance {
+    // the most common parent color, mixed in linear RGB when there is none
+    Majority,
+    LinearRgb,
+    Hsv,
+}
+
+impl Inheritance {
+    pub fn name(&self) -> &str {
+        match self {
+            Inheritance::Majority => "majority color",
+            Inheritance::LinearRgb => "linear RGB mix",
+            Inheritance::Hsv => "HSV mix",
+        }
+    }
+
+    // parents are walked more than once, so any iterator over them must be cheap to clone
+    pub fn inherit<I>(&self, parents: I) -> Color
+    where
+        I: Iterator<Item = Color> + Clone,
+    {
+        match self {
+            Inheritance::Majority => {
+                // the distinct parent colors in order of appearance with their counts,
+                // a Moore birth has at most 8 parents
+                const MAX: usize = 8;
+                let mut counts = [(DEAD, 0); MAX];
+                let (mut distinct, mut total) = (0, 0);
+                for parent in parents.clone() {
+                    total += 1;
+                    match counts[..distinct].iter_mut().find(|(color, _)| *color == parent) {
+                        Some((_, count)) => *count += 1,
+                        None if distinct < MAX => {
+                            counts[distinct] = (parent, 1);
+                            distinct += 1;
+                        }
+                        None => {}
+                    }
+                }
+                let (color, count) = counts[..distinct]
+                    .iter()
+                    .fold((DEAD, 0), |best, &next| if next.1 > best.1 { next } else { best });
+
+                if count > 1 || total == 1 {
+                    color
+                } else {
+                    Inheritance::LinearRgb.inherit(parents)
+                }
+            }
+            Inheritance::LinearRgb => {
+                let n = parents.clone().count().max(1) as f32;
+                let mut color = [0.0, 0.0, 0.0, 1.0];
+                for parent in parents {
+                    for i in 0..3 {
+                        color[i] += parent[i] / n;
+                    }
+                }
+                color
+            }
+            Inheritance::Hsv => {
+                let n = parents.clone().count().max(1) as f32;
+                let (mut x, mut y, mut saturation, mut value) = (0.0, 0.0, 0.0, 0.0);
+                for [r, g, b, _] in parents {
+                    let hsv = Hsv::from(LinSrgb::new(r, g, b));
+                    let hue = hsv.hue.to_positive_radians();
+                    // hues are averaged around the color wheel
+                    x += hue.cos() / n;
+                    y += hue.sin() / n;
+                    saturation += hsv.saturation / n;
+                    value += hsv.value / n;
+                }
+
+                let hue = y.atan2(x).to_degrees();
+                let (r, g, b) = LinSrgb::from(Hsv::new(hue, saturation, value)).into_components();
+                [r, g, b, 1.0]
+            }
+        }
+    }
+}
+
+// Life-like rule where a newborn takes its color from the live cells that bore it.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Immigration {
+    name: String,
+    life: LifeLike,
+    inheritance: Inheritance,
+}
+
+impl Immigration {
+    pub fn new(life: LifeLike, inheritance: Inheritance) -> Self {
+        let name = match inheritance {
+            Inheritance::Majority if life == LifeLike::conway() => String::from("Immigration"),
+            _ => format!("{} ({})", life.name(), inheritance.name()),
+        };
+
+        Self {
+            name,
+            life,
+            inheritance,
+        }
+    }
+
+    pub fn presets() -> Vec<Self> {
+        vec![
+            Self::new(LifeLike::conway(), Inheritance::Majority),
+            Self::new(LifeLike::conway(), Inheritance::LinearRgb),
+            Self::new(LifeLike::conway(), Inheritance::Hsv),
+        ]
+    }
+}
+
+impl Rule for Immigration {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn neighborhood(&self) -> &Neighborhood {
+        self.life.neighborhood()
+    }
+
+    fn step(&self, world: &World, t_cell: Cell) -> Option<Cell> {
+        let neighbors_alive = world.count_alive(t_cell.at, self.neighborhood());
+
+        if t_cell.is_alive() {
+            if self.life.survives(neighbors_alive) {
+                None
+            } else {
+                Some(Cell {
+                    color: DEAD,
+                    ..t_cell
+                })
+            }
+        } else if self.life.is_born(neighbors_alive) {
+            let parents = world
+                .neighbors(t_cell.at, self.neighborhood())
+                .filter(|(n, _)| n.is_alive())
+                .map(|(n, _)| n.color);
+            Some(Cell {
+                color: self.inheritance.inherit(parents),
+                ..t_cell
+            })
+        } else {
+            None
+        }
+    }
+
+    fn metadata(&self) -> Option<RuleMetadata> {
+        Some(RuleMetadata {
+            description: Some(format!("newborn cells take the {} of their parents", self.inheritance.name())),
+            author: None,
+            rulestring: Some(self.life.to_string()),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RED: Color = [1.0, 0.0, 0.0, 1.0];
+    const BLUE: Color = [0.0, 0.0, 1.0, 1.0];
+    const WHITE: Color = [1.0, 1.0, 1.0, 1.0];
+
+    fn born(inheritance: Inheritance, parents: [Color; 3]) -> Color {
+        let mut world = World::new(8, 8);
+        for (col, &color) in parents.iter().enumerate() {
+            let cell = world.find_cell_at(2, col + 2).unwrap();
+            world.write(Cell { color, ..cell });
+        }
+        let rule = Immigration::new(LifeLike::conway(), inheritance);
+        rule.step(&world, world.find_cell_at(3, 3).unwrap()).unwrap().color
+    }
+
+    #[test]
+    fn newborns_take_the_majority_color() {
+        assert_eq!(born(Inheritance::Majority, [RED, BLUE, RED]), RED);
+        assert_eq!(born(Inheritance::Majority, [BLUE, BLUE, RED]), BLUE);
+    }
+
+    #[test]
+    fn majority_without_a_winner_mixes() {
+        let color = born(Inheritance::Majority, [RED, BLUE, WHITE]);
+        assert_eq!(color, born(Inheritance::LinearRgb, [RED, BLUE, WHITE]));
+        assert_eq!(color, [2.0 / 3.0, 1.0 / 3.0, 2.0 / 3.0, 1.0]);
+    }
+
+    #[test]
+    fn hsv_mixes_hues_around_the_wheel() {
+        // a red parent at 0 degrees and two magenta ones at 300 average near 320, a plain mean gives 200
+        let magenta = [1.0, 0.0, 1.0, 1.0];
+        let [r, g, b, a] = born(Inheritance::Hsv, [RED, magenta, magenta]);
+        assert!(r > b && b > g, "{:?}", [r, g, b]);
+        assert_eq!(a, 1.0);
+    }
+
+    #[test]
+    fn a_lone_parent_passes_its_color_on() {
+        assert_eq!(Inheritance::Majority.inherit([BLUE].iter().copied()), BLUE);
+    }
+}
@@ -0,0 +1,324 @@
+use crate::cell::{Cell, SUPER_NOVA};
+use crate::rules::generations::{decay_color, decay_state};
+use crate::neighborhood::{Neighborhood, Shape};
+use crate::rules::{parse_presets, preset_name, Rule, RuleMetadata, RuleParseError};
+use crate::world::World;
+use std::fmt;
+use std::str::FromStr;
+
+pub const PRESETS: [(&str, &str); 5] = [
+    ("Bugs", "R5,C0,M1,S34..58,B34..45,NM"),
+    ("Bosco's Rule", "R5,C0,M1,S33..57,B34..45,NM"),
+    ("Majority", "R4,C0,M1,S41..81,B41..81,NM"),
+    ("Waffle", "R7,C0,M1,S100..200,B75..170,NM"),
+    ("Globe", "R8,C0,M0,S163..223,B74..252,NM"),
+];
+
+fn shape_letter(shape: Shape) -> char {
+    match shape {
+        Shape::Moore => 'M',
+        Shape::VonNeumann => 'N',
+        Shape::Circular => 'C',
+        Shape::Cross => '+',
+        Shape::Hexagonal => 'H',
+    }
+}
+
+// including the middle cell
+fn neighborhood_size(shape: Shape, radius: usize) -> usize {
+    Neighborhood::new(shape, radius).len() + 1
+}
+
+type Range = (usize, usize);
+
+// Thresholds are given for `base_radius`, other radii scale them
+// by the ratio of the neighborhood sizes.
+#[derive(Clone, Debug, PartialEq)]
+pub struct LargerThanLife {
+    name: String,
+    states: usize,
+    middle: bool,
+    survival: Range,
+    birth: Range,
+    neighborhood: Neighborhood,
+    base_radius: usize,
+    base_survival: Range,
+    base_birth: Range,
+}
+
+impl LargerThanLife {
+    pub fn new(
+        radius: usize,
+        states: usize,
+        middle: bool,
+        survival: Range,
+        birth: Range,
+        shape: Shape,
+    ) -> Result<Self, RuleParseError> {
+        if radius == 0 {
+            return Err(RuleParseError::RadiusOutOfRange(radius));
+        }
+        if states < 2 {
+            return Err(RuleParseError::StatesOutOfRange(states));
+        }
+
+        let size = neighborhood_size(shape, radius);
+        for &count in &[survival.0, survival.1, birth.0, birth.1] {
+            if count > size {
+                return Err(RuleParseError::CountOutOfRange(count));
+            }
+        }
+        for &(min, max) in &[survival, birth] {
+            if min > max {
+                return Err(RuleParseError::InvertedRange(min, max));
+            }
+        }
+
+        let mut rule = Self {
+            name: String::new(),
+            states,
+            middle,
+            survival,
+            birth,
+            neighborhood: Neighborhood::new(shape, radius),
+            base_radius: radius,
+            base_survival: survival,
+            base_birth: birth,
+        };
+        rule.resize(radius);
+
+        Ok(rule)
+    }
+
+    pub fn bugs() -> Self {
+        PRESETS[0].1.parse().unwrap()
+    }
+
+    pub fn presets() -> Vec<Self> {
+        parse_presets(&PRESETS)
+    }
+
+    fn shape(&self) -> Shape {
+        self.neighborhood.shape().unwrap_or(Shape::Moore)
+    }
+
+    fn resize(&mut self, radius: usize) {
+        let shape = self.shape();
+        let scale = neighborhood_size(shape, radius) as f64
+            / neighborhood_size(shape, self.base_radius) as f64;
+        let scaled = |(min, max): Range| {
+            (
+                (min as f64 * scale).round() as usize,
+                (max as f64 * scale).round() as usize,
+            )
+        };
+
+        self.neighborhood = Neighborhood::new(shape, radius);
+        self.survival = scaled(self.base_survival);
+        self.birth = scaled(self.base_birth);
+
+        self.name = preset_name(&PRESETS, &self.to_string());
+    }
+
+    fn parse_count(digits: &str) -> Result<usize, RuleParseError> {
+        if let Some(c) = digits.chars().find(|c| !c.is_ascii_digit()) {
+            return Err(RuleParseError::UnexpectedChar(c));
+        }
+        digits
+            .parse()
+            .map_err(|_| RuleParseError::CountOutOfRange(usize::MAX))
+    }
+
+    fn parse_range(range: &str) -> Result<Range, RuleParseError> {
+        match range.find("..") {
+            Some(at) => Ok((
+                Self::parse_count(&range[..at])?,
+                Self::parse_count(&range[at + 2..])?,
+            )),
+            None => {
+                let count = Self::parse_count(range)?;
+                Ok((count, count))
+            }
+        }
+    }
+}
+
+impl FromStr for LargerThanLife {
+    type Err = RuleParseError;
+
+    // "R5,C0,M1,S34..58,B34..45,NM", C0 and C2 are both two-state
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if s.is_empty() {
+            return Err(RuleParseError::Empty);
+        }
+
+        let mut radius = None;
+        let mut states = 0;
+        let mut middle = false;
+        let mut survival = None;
+        let mut birth = None;
+        let mut shape = Shape::Moore;
+
+        for token in s.split(',').map(str::trim) {
+            let mut chars = token.chars();
+            let letter = chars
+                .next()
+                .ok_or(RuleParseError::UnexpectedChar(','))?
+                .to_ascii_uppercase();
+            let value = chars.as_str();
+            let unexpected = || RuleParseError::UnexpectedChar(value.chars().next().unwrap_or(letter));
+
+            match letter {
+                'R' => radius = Some(Self::parse_count(value)?),
+                'C' => states = Self::parse_count(value)?,
+                'M' => {
+                    middle = match value {
+                        "0" => false,
+                        "1" => true,
+                        _ => return Err(unexpected()),
+                    }
+                }
+                'S' => survival = Some(Self::parse_range(value)?),
+                'B' => birth = Some(Self::parse_range(value)?),
+                'N' => {
+                    shape = match value.to_ascii_uppercase().as_str() {
+                        "M" => Shape::Moore,
+                        "N" => Shape::VonNeumann,
+                        "C" => Shape::Circular,
+                        "+" => Shape::Cross,
+                        "H" => Shape::Hexagonal,
+                        _ => return Err(unexpected()),
+                    }
+                }
+                c => return Err(RuleParseError::UnexpectedChar(c)),
+            }
+        }
+
+        Self::new(
+            radius.ok_or(RuleParseError::MissingSection('R'))?,
+            states.max(2),
+            middle,
+            survival.ok_or(RuleParseError::MissingSection('S'))?,
+            birth.ok_or(RuleParseError::MissingSection('B'))?,
+            shape,
+        )
+    }
+}
+
+impl fmt::Display for LargerThanLife {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "R{},C{},M{},S{}..{},B{}..{},N{}",
+            self.radius(),
+            if self.states == 2 { 0 } else { self.states },
+            self.middle as usize,
+            self.survival.0,
+            self.survival.1,
+            self.birth.0,
+            self.birth.1,
+            shape_letter(self.shape())
+        )
+    }
+}
+
+impl Rule for LargerThanLife {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn neighborhood(&self) -> &Neighborhood {
+        &self.neighborhood
+    }
+
+    fn set_radius(&mut self, radius: usize) {
+        self.resize(radius.max(1));
+    }
+
+    fn step(&self, world: &World, t_cell: Cell) -> Option<Cell> {
+        let state = decay_state(&t_cell, self.states);
+        let mut neighbors_alive = world
+            .weighted_sum(t_cell.at, &self.neighborhood, |n| {
+                if decay_state(n, self.states) == 1 {
+                    1.0
+                } else {
+                    0.0
+                }
+            })
+            .round() as usize;
+        if self.middle && state == 1 {
+            neighbors_alive += 1;
+        }
+
+        let within = |(min, max): Range| neighbors_alive >= min && neighbors_alive <= max;
+
+        let next_state = match state {
+            0 if within(self.birth) => 1,
+            0 => 0,
+            1 if within(self.survival) => 1,
+            s => (s + 1) % self.states,
+        };
+
+        if next_state == state {
+            None
+        } else if state == 0 {
+            Some(Cell {
+                color: SUPER_NOVA,
+                ..t_cell
+            })
+        } else {
+            Some(Cell {
+                color: decay_color(t_cell.color, next_state, self.states),
+                ..t_cell
+            })
+        }
+    }
+
+    fn metadata(&self) -> Option<RuleMetadata> {
+        Some(RuleMetadata {
+            description: Some(String::from(
+                "range totalistic rule, the radius keys rescale its thresholds",
+            )),
+            author: None,
+            rulestring: Some(self.to_string()),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn presets_round_trip() {
+        for (name, rulestring) in PRESETS.iter() {
+            let rule: LargerThanLife = rulestring.parse().unwrap();
+            assert_eq!(rule.to_string(), *rulestring);
+            assert_eq!(rule.name(), *name);
+        }
+    }
+
+    #[test]
+    fn parses_loose_forms() {
+        let rule: LargerThanLife = " r2, s5..8 ,B3,nn ".parse().unwrap();
+        assert_eq!(rule.to_string(), "R2,C0,M0,S5..8,B3..3,NN");
+        assert_eq!(rule.radius(), 2);
+    }
+
+    #[test]
+    fn rejects_bad_rulestrings() {
+        let error = |s: &str| s.parse::<LargerThanLife>().unwrap_err();
+        assert_eq!(error(""), RuleParseError::Empty);
+        assert_eq!(error("R2,C3,S5..3,B2"), RuleParseError::InvertedRange(5, 3));
+        assert_eq!(error("R2,S2,B9..4"), RuleParseError::InvertedRange(9, 4));
+        assert_eq!(error("R2,B3"), RuleParseError::MissingSection('S'));
+        assert_eq!(error("S2,B3"), RuleParseError::MissingSection('R'));
+        assert_eq!(error("R0,S2,B3"), RuleParseError::RadiusOutOfRange(0));
+        assert_eq!(error("R1,S2,B30"), RuleParseError::CountOutOfRange(30));
+        assert_eq!(error("R1,M2,S2,B3"), RuleParseError::UnexpectedChar('2'));
+        assert_eq!(error("R1,S2,B3,NX"), RuleParseError::UnexpectedChar('X'));
+        assert_eq!(error("R1,S2x,B3"), RuleParseError::UnexpectedChar('x'));
+        assert_eq!(error("R1,S2,B3,Q1"), RuleParseError::UnexpectedChar('Q'));
+    }
+}
@@ -0,0 +1,251 @@
+use crate::cell::{Cell, DEAD};
+use crate::neighborhood::Neighborhood;
+use crate::rules::{Rule, RuleMetadata};
+use crate::world::World;
+use graphics::types::Color;
+use palette::{Gradient, LinSrgb};
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct LeniaParameters {
+    pub name: &'static str,
+    pub radius: usize,
+    // time step, 1 / T
+    pub dt: f64,
+    // growth center and width
+    pub mu: f64,
+    pub sigma: f64,
+    // peak heights of the kernel rings
+    pub rings: &'static [f64],
+}
+
+pub const PRESETS: [LeniaParameters; 2] = [
+    LeniaParameters {
+        name: "Orbium",
+        radius: 13,
+        dt: 0.1,
+        mu: 0.15,
+        sigma: 0.015,
+        rings: &[1.0],
+    },
+    LeniaParameters {
+        name: "Hydrogeminium",
+        radius: 18,
+        dt: 0.5,
+        mu: 0.26,
+        sigma: 0.036,
+        rings: &[0.5, 1.0, 2.0 / 3.0],
+    },
+];
+
+pub fn colormap() -> Gradient<LinSrgb> {
+    Gradient::with_domain(vec![
+        (0.0, LinSrgb::new(0.0, 0.0, 0.1)),
+        (0.25, LinSrgb::new(0.0, 0.05, 0.5)),
+        (0.5, LinSrgb::new(0.0, 0.5, 0.6)),
+        (0.75, LinSrgb::new(0.9, 0.8, 0.1)),
+        (1.0, LinSrgb::new(1.0, 0.2, 0.0)),
+    ])
+}
+
+// the value a continuous rule reads from a cell, painted colors count by their brightest chanel
+pub fn value_of(cell: &Cell) -> f64 {
+    let [r, g, b, a] = cell.color;
+    (r.max(g).max(b) * a).clamp(0.0, 1.0) as f64
+}
+
+// a continuous cell drawn through a colormap, dead cells stay black
+pub fn paint(colormap: &Gradient<LinSrgb>, cell: &Cell) -> Color {
+    let value = value_of(cell);
+    if value > 0.0 {
+        let (r, g, b) = colormap.get(value as f32).into_components();
+        [r, g, b, 1.0]
+    } else {
+        DEAD
+    }
+}
+
+pub fn color_of(value: f64) -> Color {
+    if value > 0.0 {
+        let v = value as f32;
+        [v, v, v, 1.0]
+    } else {
+        DEAD
+    }
+}
+
+// Continuous cells grow by a gaussian of their ring kernel weighted surroundings.
+#[derive(Clone, Debug)]
+pub struct Lenia {
+    name: String,
+    parameters: LeniaParameters,
+    neighborhood: Neighborhood,
+    colormap: Gradient<LinSrgb>,
+}
+
+impl Lenia {
+    pub fn new(parameters: LeniaParameters) -> Self {
+        let neighborhood = Self::kernel(parameters.radius, parameters.rings);
+
+        Self {
+            name: format!("Lenia {}", parameters.name),
+            parameters,
+            neighborhood,
+            colormap: colormap(),
+        }
+    }
+
+    pub fn orbium() -> Self {
+        Self::new(PRESETS[0].clone())
+    }
+
+    pub fn presets() -> Vec<Self> {
+        PRESETS.iter().cloned().map(Self::new).collect()
+    }
+
+    pub fn parameters(&self) -> &LeniaParameters {
+        &self.parameters
+    }
+
+    // concentric bumps, normalized to sum up to 1
+    fn kernel(radius: usize, rings: &[f64]) -> Neighborhood {
+        let shell = |row: isize, col: isize| {
+            let distance = ((row * row + col * col) as f64).sqrt() / radius as f64 * rings.len() as f64;
+            if distance == 0.0 || distance >= rings.len() as f64 {
+                return 0.0;
+            }
+            let ring = distance.floor();
+            let r = distance - ring;
+            let core = if r > 0.0 { (4.0 - 1.0 / (r * (1.0 - r))).exp() } else { 0.0 };
+            rings[ring as usize] * core
+        };
+
+        let total: f64 = Neighborhood::from_fn(radius, shell).total_weight();
+        Neighborhood::from_fn(radius, |row, col| shell(row, col) / total)
+    }
+
+    pub fn growth(&self, potential: f64) -> f64 {
+        let LeniaParameters { mu, sigma, .. } = self.parameters;
+        2.0 * (-(potential - mu).powi(2) / (2.0 * sigma * sigma)).exp() - 1.0
+    }
+}
+
+impl Rule for Lenia {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn neighborhood(&self) -> &Neighborhood {
+        &self.neighborhood
+    }
+
+    fn set_radius(&mut self, radius: usize) {
+        self.parameters.radius = radius.max(2);
+        self.neighborhood = Self::kernel(self.parameters.radius, self.parameters.rings);
+    }
+
+    fn step(&self, world: &World, t_cell: Cell) -> Option<Cell> {
+        let value = value_of(&t_cell);
+        let potential = world.weighted_sum(t_cell.at, &self.neighborhood, value_of);
+        let next = (value + self.parameters.dt * self.growth(potential)).clamp(0.0, 1.0);
+
+        if next == value {
+            None
+        } else {
+            Some(Cell {
+                color: color_of(next),
+                ..t_cell
+            })
+        }
+    }
+
+    fn paint(&self, cell: &Cell) -> Color {
+        paint(&self.colormap, cell)
+    }
+
+    fn metadata(&self) -> Option<RuleMetadata> {
+        let LeniaParameters {
+            radius,
+            dt,
+            mu,
+            sigma,
+            rings,
+            ..
+        } = self.parameters;
+
+        Some(RuleMetadata {
+            description: Some(String::from(
+                "continuous cells with a ring kernel and gaussian growth",
+            )),
+            author: Some(String::from("Bert Wang-Chak Chan")),
+            rulestring: Some(format!(
+                "R={},T={},m={},s={},b={:?}",
+                radius,
+                (1.0 / dt).round(),
+                mu,
+                sigma,
+                rings
+            )),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // a patch of random values in the middle of a dead world
+    fn patch(size: usize) -> World {
+        let mut world = World::new(size, size);
+        world.set_seed(7);
+        for cell in world.get_cells() {
+            let (row, col) = cell.at;
+            if (size / 4..size * 3 / 4).contains(&row) && (size / 4..size * 3 / 4).contains(&col) {
+                let color = color_of(world.random(cell.at, 0));
+                world.write(Cell { color, ..cell });
+            }
+        }
+        world
+    }
+
+    fn values(world: &World) -> Vec<f64> {
+        world.get_cells().iter().map(value_of).collect()
+    }
+
+    #[test]
+    fn kernels_sum_up_to_one() {
+        for rule in Lenia::presets() {
+            let total = rule.neighborhood().total_weight();
+            assert!((total - 1.0).abs() < 1e-9, "{} {}", rule.name(), total);
+            assert_eq!(rule.neighborhood().weight(0, 0), 0.0);
+        }
+    }
+
+    #[test]
+    fn growth_peaks_at_mu() {
+        let rule = Lenia::orbium();
+        let mu = rule.parameters().mu;
+        assert_eq!(rule.growth(mu), 1.0);
+        assert!(rule.growth(0.0) < -0.99);
+        assert!(rule.growth(mu + 0.01) < 1.0 && rule.growth(mu + 0.01) > 0.0);
+    }
+
+    #[test]
+    fn values_stay_in_range() {
+        for rule in Lenia::presets() {
+            let mut world = patch(40);
+            for _ in 0..2 {
+                world.step(&rule);
+                let values = values(&world);
+                assert!(values.iter().all(|v| (0.0..=1.0).contains(v)), "{}", rule.name());
+                assert!(values.iter().any(|&v| v > 0.0 && v < 1.0), "{}", rule.name());
+            }
+        }
+    }
+
+    #[test]
+    fn painted_cells_count_by_their_brightest_chanel() {
+        let cell = Cell { color: [0.2, 0.8, 0.4, 0.5], at: (0, 0) };
+        assert!((value_of(&cell) - 0.4).abs() < 1e-6);
+        assert_eq!(paint(&colormap(), &Cell { color: DEAD, ..cell }), DEAD);
+    }
+}
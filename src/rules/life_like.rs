@@ -0,0 +1,234 @@
+use crate::cell::{Cell, DEAD, SUPER_NOVA};
+use crate::neighborhood::Neighborhood;
+use crate::rules::{parse_presets, preset_name, Rule, RuleMetadata, RuleParseError};
+use crate::world::World;
+use std::fmt;
+use std::str::FromStr;
+
+const MAX_NEIGHBORS: usize = 8;
+
+pub const PRESETS: [(&str, &str); 8] = [
+    ("Conway's Life", "B3/S23"),
+    ("HighLife", "B36/S23"),
+    ("Day & Night", "B3678/S34678"),
+    ("Seeds", "B2/S"),
+    ("Life without Death", "B3/S012345678"),
+    ("Maze", "B3/S12345"),
+    ("Replicator", "B1357/S1357"),
+    ("2x2", "B36/S125"),
+];
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct LifeLike {
+    name: String,
+    birth: [bool; MAX_NEIGHBORS + 1],
+    survival: [bool; MAX_NEIGHBORS + 1],
+    neighborhood: Neighborhood,
+}
+
+impl LifeLike {
+    pub fn new(birth: &[usize], survival: &[usize]) -> Result<Self, RuleParseError> {
+        let mut rule = Self {
+            name: String::new(),
+            birth: [false; MAX_NEIGHBORS + 1],
+            survival: [false; MAX_NEIGHBORS + 1],
+            neighborhood: Neighborhood::moore(1),
+        };
+
+        for &n in birth {
+            *rule.birth.get_mut(n).ok_or(RuleParseError::CountOutOfRange(n))? = true;
+        }
+        for &n in survival {
+            *rule.survival.get_mut(n).ok_or(RuleParseError::CountOutOfRange(n))? = true;
+        }
+
+        rule.name = preset_name(&PRESETS, &rule.to_string());
+
+        Ok(rule)
+    }
+
+    pub fn conway() -> Self {
+        "B3/S23".parse().unwrap()
+    }
+
+    pub fn presets() -> Vec<Self> {
+        parse_presets(&PRESETS)
+    }
+
+    pub fn is_born(&self, neighbors_alive: usize) -> bool {
+        self.birth.get(neighbors_alive).copied().unwrap_or(false)
+    }
+
+    pub fn survives(&self, neighbors_alive: usize) -> bool {
+        self.survival.get(neighbors_alive).copied().unwrap_or(false)
+    }
+
+    fn parse_counts(digits: &str) -> Result<Vec<usize>, RuleParseError> {
+        digits
+            .chars()
+            .map(|c| match c.to_digit(10) {
+                Some(n) if n as usize <= MAX_NEIGHBORS => Ok(n as usize),
+                Some(n) => Err(RuleParseError::CountOutOfRange(n as usize)),
+                None => Err(RuleParseError::UnexpectedChar(c)),
+            })
+            .collect()
+    }
+}
+
+impl FromStr for LifeLike {
+    type Err = RuleParseError;
+
+    // accepts "B3/S23", "S23/B3", "B3S23" and the S/B form "23/3"
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if s.is_empty() {
+            return Err(RuleParseError::Empty);
+        }
+
+        let starts_with_letter = s.starts_with(|c: char| c.is_ascii_alphabetic());
+
+        let sections: Vec<&str> = if starts_with_letter {
+            s.split('/')
+                .flat_map(|part| {
+                    let mut parts = Vec::new();
+                    let mut start = 0;
+                    for (i, c) in part.char_indices().skip(1) {
+                        if c.is_ascii_alphabetic() {
+                            parts.push(&part[start..i]);
+                            start = i;
+                        }
+                    }
+                    parts.push(&part[start..]);
+                    parts
+                })
+                .collect()
+        } else {
+            s.split('/').collect()
+        };
+
+        let mut birth = None;
+        let mut survival = None;
+
+        if starts_with_letter {
+            for section in sections {
+                let mut chars = section.chars();
+                let (slot, letter) = match chars.next().map(|c| c.to_ascii_uppercase()) {
+                    Some('B') => (&mut birth, 'B'),
+                    Some('S') => (&mut survival, 'S'),
+                    Some(c) => return Err(RuleParseError::UnexpectedChar(c)),
+                    None => return Err(RuleParseError::UnexpectedChar('/')),
+                };
+                if slot.is_some() {
+                    return Err(RuleParseError::DuplicateSection(letter));
+                }
+                *slot = Some(Self::parse_counts(chars.as_str())?);
+            }
+        } else {
+            let mut sections = sections.into_iter();
+            survival = sections.next().map(Self::parse_counts).transpose()?;
+            birth = sections.next().map(Self::parse_counts).transpose()?;
+            if sections.next().is_some() {
+                return Err(RuleParseError::UnexpectedChar('/'));
+            }
+        }
+
+        let birth = birth.ok_or(RuleParseError::MissingSection('B'))?;
+        let survival = survival.ok_or(RuleParseError::MissingSection('S'))?;
+
+        Self::new(&birth, &survival)
+    }
+}
+
+impl fmt::Display for LifeLike {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let counts = |set: &[bool]| -> String {
+            set.iter()
+                .enumerate()
+                .filter(|(_, on)| **on)
+                .map(|(n, _)| n.to_string())
+                .collect()
+        };
+        write!(f, "B{}/S{}", counts(&self.birth), counts(&self.survival))
+    }
+}
+
+impl Rule for LifeLike {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn neighborhood(&self) -> &Neighborhood {
+        &self.neighborhood
+    }
+
+    fn step(&self, world: &World, t_cell: Cell) -> Option<Cell> {
+        let neighbors_alive = world.count_alive(t_cell.at, &self.neighborhood);
+
+        if t_cell.is_alive() {
+            if self.survives(neighbors_alive) {
+                None
+            } else {
+                Some(Cell {
+                    color: DEAD,
+                    ..t_cell
+                })
+            }
+        } else if self.is_born(neighbors_alive) {
+            Some(Cell {
+                color: SUPER_NOVA,
+                ..t_cell
+            })
+        } else {
+            None
+        }
+    }
+
+    fn as_life_like(&self) -> Option<&LifeLike> {
+        Some(self)
+    }
+
+    fn metadata(&self) -> Option<RuleMetadata> {
+        Some(RuleMetadata {
+            description: Some(String::from("outer totalistic rule on the Moore neighborhood")),
+            author: None,
+            rulestring: Some(self.to_string()),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn presets_round_trip() {
+        for (name, rulestring) in PRESETS.iter() {
+            let rule: LifeLike = rulestring.parse().unwrap();
+            assert_eq!(rule.to_string(), *rulestring);
+            assert_eq!(rule.name(), *name);
+        }
+    }
+
+    #[test]
+    fn parses_every_notation() {
+        for rulestring in ["B3/S23", "S23/B3", "B3S23", "b3s23", "23/3", " B3/S23 "].iter() {
+            let rule: LifeLike = rulestring.parse().unwrap();
+            assert_eq!(rule, LifeLike::conway(), "{}", rulestring);
+        }
+        let seeds: LifeLike = "B2/S".parse().unwrap();
+        assert!(seeds.is_born(2) && !(0..=8).any(|n| seeds.survives(n)));
+    }
+
+    #[test]
+    fn rejects_bad_rulestrings() {
+        let error = |s: &str| s.parse::<LifeLike>().unwrap_err();
+        assert_eq!(error(""), RuleParseError::Empty);
+        assert_eq!(error("B3"), RuleParseError::MissingSection('S'));
+        assert_eq!(error("S23"), RuleParseError::MissingSection('B'));
+        assert_eq!(error("B3/S23/B3"), RuleParseError::DuplicateSection('B'));
+        assert_eq!(error("B39/S23"), RuleParseError::CountOutOfRange(9));
+        assert_eq!(error("B3/S2-"), RuleParseError::UnexpectedChar('-'));
+        assert_eq!(error("B3/X23"), RuleParseError::UnexpectedChar('X'));
+        assert_eq!(error("23/3/4"), RuleParseError::UnexpectedChar('/'));
+    }
+}
@@ -0,0 +1,254 @@
+use crate::boundary::Boundary;
+use crate::cell::{Cell, DEAD, SUPER_NOVA};
+use crate::neighborhood::Neighborhood;
+use crate::rules::{parse_presets, preset_name, Rule, RuleMetadata, RuleParseError};
+use crate::world::World;
+use std::fmt;
+use std::str::FromStr;
+
+// a block counts its cells as top left 1, top right 2, bottom left 4 and bottom right 8
+const BLOCKS: usize = 16;
+
+pub const PRESETS: [(&str, &str); 4] = [
+    ("Critters", "MS,D15;14;13;3;11;5;6;1;7;9;10;2;12;4;8;0"),
+    ("Tron", "MS,D15;1;2;3;4;5;6;7;8;9;10;11;12;13;14;0"),
+    ("BBM", "MS,D0;8;4;3;2;5;9;7;1;6;10;11;12;13;14;15"),
+    ("Sand", "MS,D0;4;8;12;4;12;12;13;8;12;12;14;12;13;14;15"),
+];
+
+// Partitions the grid into 2x2 blocks, shifted by one cell every other generation,
+// and replaces every block through a table. Worlds step it synchronously.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Margolus {
+    name: String,
+    table: [usize; BLOCKS],
+    neighborhood: Neighborhood,
+}
+
+impl Margolus {
+    pub fn new(table: [usize; BLOCKS]) -> Result<Self, RuleParseError> {
+        if let Some(&block) = table.iter().find(|&&block| block >= BLOCKS) {
+            return Err(RuleParseError::BlockOutOfRange(block));
+        }
+
+        let mut rule = Self {
+            name: String::new(),
+            table,
+            neighborhood: Neighborhood::moore(1),
+        };
+
+        rule.name = preset_name(&PRESETS, &rule.to_string());
+
+        Ok(rule)
+    }
+
+    pub fn presets() -> Vec<Self> {
+        parse_presets(&PRESETS)
+    }
+
+    pub fn table(&self) -> &[usize; BLOCKS] {
+        &self.table
+    }
+
+    // a table is reversible when it permutes the blocks
+    pub fn is_reversible(&self) -> bool {
+        let mut seen = [false; BLOCKS];
+        for &block in self.table.iter() {
+            seen[block] = true;
+        }
+        seen.iter().all(|&s| s)
+    }
+}
+
+impl FromStr for Margolus {
+    type Err = RuleParseError;
+
+    // accepts the MCell notation "MS,D15;14;13;3;11;5;6;1;7;9;10;2;12;4;8;0"
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if s.is_empty() {
+            return Err(RuleParseError::Empty);
+        }
+
+        let s = s
+            .strip_prefix("MS,")
+            .or_else(|| s.strip_prefix("ms,"))
+            .ok_or(RuleParseError::MissingSection('M'))?;
+        let s = s
+            .strip_prefix(['D', 'd'])
+            .ok_or(RuleParseError::MissingSection('D'))?;
+
+        let blocks = s
+            .split(';')
+            .map(|block| {
+                if let Some(c) = block.chars().find(|c| !c.is_ascii_digit()) {
+                    return Err(RuleParseError::UnexpectedChar(c));
+                }
+                block
+                    .parse::<usize>()
+                    .map_err(|_| RuleParseError::UnexpectedChar(';'))
+            })
+            .collect::<Result<Vec<usize>, _>>()?;
+
+        if blocks.len() != BLOCKS {
+            return Err(RuleParseError::TableLength(blocks.len()));
+        }
+
+        let mut table = [0; BLOCKS];
+        table.copy_from_slice(&blocks);
+
+        Self::new(table)
+    }
+}
+
+impl fmt::Display for Margolus {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let table: Vec<String> = self.table.iter().map(|block| block.to_string()).collect();
+        write!(f, "MS,D{}", table.join(";"))
+    }
+}
+
+impl Rule for Margolus {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn neighborhood(&self) -> &Neighborhood {
+        &self.neighborhood
+    }
+
+    fn step(&self, world: &World, t_cell: Cell) -> Option<Cell> {
+        let shift = world.generation() % 2;
+        let (row, col) = t_cell.at;
+        // where the cell sits inside its block
+        let (y, x) = ((row + shift) % 2, (col + shift) % 2);
+
+        // Blocks only pair up across the edge of a torus of even size. Anywhere
+        // else a block crossing the edge would hold cells of other blocks or of
+        // the border, so it keeps its cells as they are.
+        let torus = world.boundary() == Boundary::Torus;
+        let whole = |at: usize, inside: usize, size: usize| {
+            (at >= inside && at - inside + 1 < size) || (torus && size.is_multiple_of(2))
+        };
+        if !whole(row, y, world.rows()) || !whole(col, x, world.cols()) {
+            return None;
+        }
+
+        let block = [(0, 0), (0, 1), (1, 0), (1, 1)]
+            .iter()
+            .enumerate()
+            .filter(|(_, &(d_row, d_col))| {
+                world
+                    .relative(t_cell.at, (d_row - y as isize, d_col - x as isize))
+                    .is_alive()
+            })
+            .fold(0, |block, (i, _)| block | 1 << i);
+
+        let alive = self.table[block] & 1 << (y * 2 + x) != 0;
+
+        match (t_cell.is_alive(), alive) {
+            (false, true) => Some(Cell {
+                color: SUPER_NOVA,
+                ..t_cell
+            }),
+            (true, false) => Some(Cell {
+                color: DEAD,
+                ..t_cell
+            }),
+            _ => None,
+        }
+    }
+
+    // the blocks shift every other generation
+    fn time_invariant(&self) -> bool {
+        false
+    }
+
+    // the cells of a block are replaced together
+    fn synchronous_only(&self) -> bool {
+        true
+    }
+
+    fn metadata(&self) -> Option<RuleMetadata> {
+        let description = if self.is_reversible() {
+            "reversible block rule on the Margolus neighborhood"
+        } else {
+            "block rule on the Margolus neighborhood"
+        };
+
+        Some(RuleMetadata {
+            description: Some(String::from(description)),
+            author: None,
+            rulestring: Some(self.to_string()),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schedule::Schedule;
+
+    #[test]
+    fn presets_round_trip() {
+        for (name, rulestring) in PRESETS.iter() {
+            let rule: Margolus = rulestring.parse().unwrap();
+            assert_eq!(rule.to_string(), *rulestring);
+            assert_eq!(rule.name(), *name);
+        }
+    }
+
+    #[test]
+    fn parses_lowercase() {
+        let rule: Margolus = " ms,d15;1;2;3;4;5;6;7;8;9;10;11;12;13;14;0 ".parse().unwrap();
+        assert_eq!(rule.name(), "Tron");
+    }
+
+    #[test]
+    fn rejects_bad_rulestrings() {
+        let error = |s: &str| s.parse::<Margolus>().unwrap_err();
+        assert_eq!(error(""), RuleParseError::Empty);
+        assert_eq!(error("D0;1"), RuleParseError::MissingSection('M'));
+        assert_eq!(error("MS,0;1"), RuleParseError::MissingSection('D'));
+        assert_eq!(error("MS,D0;1;2"), RuleParseError::TableLength(3));
+        assert_eq!(error("MS,D0;1;2;x"), RuleParseError::UnexpectedChar('x'));
+        assert_eq!(error("MS,D0;;2"), RuleParseError::UnexpectedChar(';'));
+        assert_eq!(
+            error("MS,D16;1;2;3;4;5;6;7;8;9;10;11;12;13;14;15"),
+            RuleParseError::BlockOutOfRange(16)
+        );
+    }
+
+    #[test]
+    fn billiard_balls_are_conserved() {
+        let bbm: Margolus = PRESETS[2].1.parse().unwrap();
+        // odd sizes, even ones, and a schedule that would split the blocks
+        let worlds = [
+            (50, 75, Boundary::Torus, Schedule::Synchronous),
+            (51, 76, Boundary::Torus, Schedule::Synchronous),
+            (50, 76, Boundary::Torus, Schedule::Checkerboard),
+            (50, 76, Boundary::Torus, Schedule::Alpha(0.5)),
+            (49, 75, Boundary::Dead, Schedule::Synchronous),
+            (50, 76, Boundary::KleinBottle, Schedule::Synchronous),
+        ];
+
+        for &(rows, cols, boundary, schedule) in worlds.iter() {
+            let mut world = World::new(rows, cols);
+            world.set_boundary(boundary);
+            world.set_schedule(schedule);
+            for cell in world.get_cells() {
+                let (row, col) = cell.at;
+                if (row * 7 + col * 13 + row * col) % 5 == 0 {
+                    world.write(Cell { color: SUPER_NOVA, ..cell });
+                }
+            }
+            let population = |world: &World| world.get_cells().iter().filter(|c| c.is_alive()).count();
+            let start = population(&world);
+
+            for _ in 0..20 {
+                world.step(&bbm);
+                assert_eq!(population(&world), start, "{}x{} {:?} {:?}", rows, cols, boundary, schedule);
+            }
+        }
+    }
+}
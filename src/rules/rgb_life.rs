@@ -0,0 +1,193 @@
+use crate::cell::{Cell, Chanels, DEAD};
+use crate::neighborhood::Neighborhood;
+use crate::rules::{LifeLike, Rule, RuleMetadata};
+use crate::world::World;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Interaction {
+    Independent,
+    // a chanel is eaten when its predator outnumbers it around the cell
+    RockPaperScissors,
+}
+
+// Every color chanel is a species of its own with its own Life-like rule,
+// newborns take the average intensity of their parents in that chanel.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RgbLife {
+    name: String,
+    chanels: [LifeLike; 3],
+    interaction: Interaction,
+}
+
+impl RgbLife {
+    pub fn new(life: LifeLike, interaction: Interaction) -> Self {
+        Self::with_chanels([life.clone(), life.clone(), life], interaction)
+    }
+
+    pub fn with_chanels(chanels: [LifeLike; 3], interaction: Interaction) -> Self {
+        let name = match interaction {
+            Interaction::Independent => format!("RGB {}", chanels[0].name()),
+            Interaction::RockPaperScissors => format!("RGB {} RPS", chanels[0].name()),
+        };
+
+        Self {
+            name,
+            chanels,
+            interaction,
+        }
+    }
+
+    pub fn presets() -> Vec<Self> {
+        vec![
+            Self::new(LifeLike::conway(), Interaction::Independent),
+            Self::new(LifeLike::conway(), Interaction::RockPaperScissors),
+        ]
+    }
+
+    pub fn life(&self, ch: &Chanels) -> &LifeLike {
+        &self.chanels[ch.index()]
+    }
+}
+
+impl Rule for RgbLife {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn neighborhood(&self) -> &Neighborhood {
+        self.chanels[0].neighborhood()
+    }
+
+    fn step(&self, world: &World, t_cell: Cell) -> Option<Cell> {
+        let neighborhood = self.neighborhood();
+        let mut alive = [0; 3];
+        let mut intensity = [0.0; 3];
+
+        for ch in Chanels::ALL.iter() {
+            let i = ch.index();
+            alive[i] = world
+                .weighted_sum(t_cell.at, neighborhood, |n| {
+                    if n.is_chanel_alive(ch) {
+                        1.0
+                    } else {
+                        0.0
+                    }
+                })
+                .round() as usize;
+            if alive[i] > 0 {
+                let total = world.weighted_sum(t_cell.at, neighborhood, |n| n.chanel(ch) as f64);
+                intensity[i] = (total / alive[i] as f64) as f32;
+            }
+        }
+
+        let mut color = DEAD;
+        for ch in Chanels::ALL.iter() {
+            let i = ch.index();
+            let life = self.life(ch);
+            let eaten = self.interaction == Interaction::RockPaperScissors
+                && alive[ch.predator().index()] > alive[i];
+
+            color[i] = if eaten {
+                0.0
+            } else if t_cell.is_chanel_alive(ch) {
+                if life.survives(alive[i]) {
+                    t_cell.chanel(ch)
+                } else {
+                    0.0
+                }
+            } else if life.is_born(alive[i]) {
+                intensity[i]
+            } else {
+                0.0
+            };
+        }
+        if color[..3].iter().any(|c| *c > 0.0) {
+            color[3] = 1.0;
+        }
+
+        if color == t_cell.color {
+            None
+        } else {
+            Some(Cell { color, ..t_cell })
+        }
+    }
+
+    fn metadata(&self) -> Option<RuleMetadata> {
+        Some(RuleMetadata {
+            description: Some(String::from(
+                "red, green and blue chanels each run their own Life-like rule",
+            )),
+            author: None,
+            rulestring: Some(
+                Chanels::ALL
+                    .iter()
+                    .map(|ch| self.life(ch).to_string())
+                    .collect::<Vec<String>>()
+                    .join(","),
+            ),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use graphics::types::Color;
+
+    const RED: Color = [1.0, 0.0, 0.0, 1.0];
+    const GREEN: Color = [0.0, 1.0, 0.0, 1.0];
+
+    fn world(cells: &[((usize, usize), Color)]) -> World {
+        let mut world = World::new(10, 10);
+        for &((row, col), color) in cells.iter() {
+            let cell = world.find_cell_at(row, col).unwrap();
+            world.write(Cell { color, ..cell });
+        }
+        world
+    }
+
+    // a green cell between two green ones, with three red cells around it
+    fn contested() -> World {
+        world(&[
+            ((4, 5), GREEN),
+            ((5, 5), GREEN),
+            ((6, 5), GREEN),
+            ((4, 4), RED),
+            ((5, 4), RED),
+            ((6, 4), RED),
+        ])
+    }
+
+    #[test]
+    fn chanels_live_apart() {
+        let rule = RgbLife::new(LifeLike::conway(), Interaction::Independent);
+        let world = contested();
+        // green survives on two green neighbors and red is born from three
+        let center = rule.step(&world, world.find_cell_at(5, 5).unwrap()).unwrap();
+        assert_eq!(center.color, [1.0, 1.0, 0.0, 1.0]);
+    }
+
+    #[test]
+    fn predators_eat_outnumbered_chanels() {
+        let rule = RgbLife::new(LifeLike::conway(), Interaction::RockPaperScissors);
+        let world = contested();
+        // red eats green, three red neighbors outnumber two green ones
+        let center = rule.step(&world, world.find_cell_at(5, 5).unwrap()).unwrap();
+        assert_eq!(center.color, RED);
+        // next to it three green neighbors outnumber two red ones, green is born and red survives
+        let side = rule.step(&world, world.find_cell_at(5, 4).unwrap()).unwrap();
+        assert_eq!(side.color, [1.0, 1.0, 0.0, 1.0]);
+    }
+
+    #[test]
+    fn newborns_average_their_parents() {
+        let rule = RgbLife::new(LifeLike::conway(), Interaction::Independent);
+        let world = world(&[
+            ((4, 4), [1.0, 0.0, 0.0, 1.0]),
+            ((4, 5), [0.5, 0.0, 0.0, 1.0]),
+            ((4, 6), [0.75, 0.0, 0.0, 1.0]),
+        ]);
+        let born = rule.step(&world, world.find_cell_at(5, 5).unwrap()).unwrap();
+        assert_eq!(born.color, [0.75, 0.0, 0.0, 1.0]);
+    }
+}
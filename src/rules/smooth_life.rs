@@ -0,0 +1,235 @@
+use crate::cell::Cell;
+use crate::neighborhood::Neighborhood;
+use crate::rules::lenia::{color_of, colormap, paint, value_of};
+use crate::rules::{Rule, RuleMetadata};
+use crate::world::World;
+use graphics::types::Color;
+use palette::{Gradient, LinSrgb};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Stepping {
+    // the transition replaces the cell
+    Discrete,
+    // the cell moves towards the transition by dt
+    Continuous(f64),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct SmoothLifeParameters {
+    pub name: &'static str,
+    // outer radius, the inner disk is a third of it
+    pub radius: usize,
+    // birth and death intervals of the annulus fill
+    pub birth: (f64, f64),
+    pub death: (f64, f64),
+    // sigmoid widths over the annulus and the disk fill
+    pub alpha_n: f64,
+    pub alpha_m: f64,
+    pub stepping: Stepping,
+}
+
+pub const PRESETS: [SmoothLifeParameters; 2] = [
+    SmoothLifeParameters {
+        name: "SmoothLife",
+        radius: 10,
+        birth: (0.278, 0.365),
+        death: (0.267, 0.445),
+        alpha_n: 0.028,
+        alpha_m: 0.147,
+        stepping: Stepping::Discrete,
+    },
+    SmoothLifeParameters {
+        name: "SmoothLife Flow",
+        radius: 10,
+        birth: (0.254, 0.312),
+        death: (0.340, 0.518),
+        alpha_n: 0.028,
+        alpha_m: 0.147,
+        stepping: Stepping::Continuous(0.1),
+    },
+];
+
+fn sigmoid(x: f64, a: f64, alpha: f64) -> f64 {
+    1.0 / (1.0 + (-(x - a) * 4.0 / alpha).exp())
+}
+
+// Life on a continuous grid, cells compare the fill of an inner disk and the annulus around it.
+#[derive(Clone, Debug)]
+pub struct SmoothLife {
+    name: String,
+    parameters: SmoothLifeParameters,
+    disk: Neighborhood,
+    annulus: Neighborhood,
+    colormap: Gradient<LinSrgb>,
+}
+
+impl SmoothLife {
+    pub fn new(parameters: SmoothLifeParameters) -> Self {
+        let (disk, annulus) = Self::masks(parameters.radius);
+
+        Self {
+            name: String::from(parameters.name),
+            parameters,
+            disk,
+            annulus,
+            colormap: colormap(),
+        }
+    }
+
+    pub fn presets() -> Vec<Self> {
+        PRESETS.iter().cloned().map(Self::new).collect()
+    }
+
+    pub fn parameters(&self) -> &SmoothLifeParameters {
+        &self.parameters
+    }
+
+    // antialiased disk and annulus, each normalized to sum up to 1
+    fn masks(radius: usize) -> (Neighborhood, Neighborhood) {
+        let outer = radius as f64;
+        let inner = outer / 3.0;
+        let coverage = |r: f64, row: isize, col: isize| {
+            let distance = ((row * row + col * col) as f64).sqrt();
+            (r + 0.5 - distance).clamp(0.0, 1.0)
+        };
+        let disk = |row, col| coverage(inner, row, col);
+        let annulus = |row, col| coverage(outer, row, col) - coverage(inner, row, col);
+
+        let disk_total = Neighborhood::from_fn(radius, disk).total_weight();
+        let annulus_total = Neighborhood::from_fn(radius, annulus).total_weight();
+
+        (
+            Neighborhood::from_fn(radius, |row, col| disk(row, col) / disk_total),
+            Neighborhood::from_fn(radius, |row, col| annulus(row, col) / annulus_total),
+        )
+    }
+
+    // the next value for a disk fill m and an annulus fill n
+    pub fn transition(&self, n: f64, m: f64) -> f64 {
+        let SmoothLifeParameters {
+            birth: (b1, b2),
+            death: (d1, d2),
+            alpha_n,
+            alpha_m,
+            ..
+        } = self.parameters;
+
+        let alive = sigmoid(m, 0.5, alpha_m);
+        let lower = b1 * (1.0 - alive) + d1 * alive;
+        let upper = b2 * (1.0 - alive) + d2 * alive;
+
+        sigmoid(n, lower, alpha_n) * (1.0 - sigmoid(n, upper, alpha_n))
+    }
+}
+
+impl Rule for SmoothLife {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn neighborhood(&self) -> &Neighborhood {
+        &self.annulus
+    }
+
+    fn set_radius(&mut self, radius: usize) {
+        self.parameters.radius = radius.max(3);
+        let (disk, annulus) = Self::masks(self.parameters.radius);
+        self.disk = disk;
+        self.annulus = annulus;
+    }
+
+    fn step(&self, world: &World, t_cell: Cell) -> Option<Cell> {
+        let value = value_of(&t_cell);
+        let m = world.weighted_sum(t_cell.at, &self.disk, value_of);
+        let n = world.weighted_sum(t_cell.at, &self.annulus, value_of);
+        let s = self.transition(n, m);
+
+        let next = match self.parameters.stepping {
+            Stepping::Discrete => s,
+            Stepping::Continuous(dt) => (value + dt * (2.0 * s - 1.0)).clamp(0.0, 1.0),
+        };
+
+        if next == value {
+            None
+        } else {
+            Some(Cell {
+                color: color_of(next),
+                ..t_cell
+            })
+        }
+    }
+
+    fn paint(&self, cell: &Cell) -> Color {
+        paint(&self.colormap, cell)
+    }
+
+    fn metadata(&self) -> Option<RuleMetadata> {
+        let SmoothLifeParameters {
+            radius,
+            birth: (b1, b2),
+            death: (d1, d2),
+            stepping,
+            ..
+        } = self.parameters;
+
+        let stepping = match stepping {
+            Stepping::Discrete => String::from("discrete"),
+            Stepping::Continuous(dt) => format!("dt={}", dt),
+        };
+
+        Some(RuleMetadata {
+            description: Some(String::from(
+                "continuous life comparing the fill of an inner disk and an outer annulus",
+            )),
+            author: Some(String::from("Stephan Rafler")),
+            rulestring: Some(format!(
+                "R={},B={}..{},D={}..{},{}",
+                radius, b1, b2, d1, d2, stepping
+            )),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn masks_sum_up_to_one() {
+        let rule = SmoothLife::new(PRESETS[0].clone());
+        for mask in [&rule.disk, &rule.annulus].iter() {
+            assert!((mask.total_weight() - 1.0).abs() < 1e-9);
+        }
+        // the annulus leaves out the disk it surrounds
+        assert_eq!(rule.annulus.weight(0, 1), 0.0);
+        assert!(rule.disk.weight(0, 1) > 0.0);
+    }
+
+    #[test]
+    fn transition_follows_the_intervals() {
+        let rule = SmoothLife::new(PRESETS[0].clone());
+        // a dead disk is born inside the birth interval only
+        assert!(rule.transition(0.32, 0.0) > 0.9);
+        assert!(rule.transition(0.1, 0.0) < 0.1);
+        // a live disk survives inside the wider death interval
+        assert!(rule.transition(0.42, 1.0) > 0.9);
+        assert!(rule.transition(0.6, 1.0) < 0.1);
+    }
+
+    #[test]
+    fn values_stay_in_range() {
+        for rule in SmoothLife::presets() {
+            let mut world = World::new(32, 32);
+            world.set_seed(7);
+            for cell in world.get_cells() {
+                let color = color_of(world.random(cell.at, 0));
+                world.write(Cell { color, ..cell });
+            }
+            for _ in 0..2 {
+                world.step(&rule);
+                let values: Vec<f64> = world.get_cells().iter().map(value_of).collect();
+                assert!(values.iter().all(|v| (0.0..=1.0).contains(v)), "{}", rule.name());
+            }
+        }
+    }
+}
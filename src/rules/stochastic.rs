@@ -0,0 +1,160 @@
+use crate::cell::{Cell, DEAD, SUPER_NOVA};
+use crate::neighborhood::Neighborhood;
+use crate::rules::{LifeLike, Rule, RuleMetadata};
+use crate::world::World;
+
+// salts of the draws a cell makes every generation
+const TRANSITION: u64 = 0;
+const NOISE: u64 = 1;
+
+pub const PRESETS: [(&str, &str, f64, f64, f64); 4] = [
+    ("Noisy Life", "B3/S23", 1.0, 1.0, 0.0005),
+    ("Unreliable Life", "B3/S23", 0.9, 0.95, 0.0),
+    ("Contact Process", "B12345678/S012345678", 0.15, 0.9, 0.0),
+    ("Flickering Seeds", "B2/S", 0.5, 1.0, 0.0001),
+];
+
+// A life-like rule whose births and survivals only happen with some probability,
+// on top of that every cell flips on its own with the noise probability.
+// The draws come from the world's seed, so a run replays given the same seed.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Stochastic {
+    name: String,
+    life: LifeLike,
+    birth: f64,
+    survival: f64,
+    noise: f64,
+}
+
+impl Stochastic {
+    pub fn new(life: LifeLike, birth: f64, survival: f64, noise: f64) -> Self {
+        let clamp = |p: f64| p.clamp(0.0, 1.0);
+        let mut rule = Self {
+            name: String::new(),
+            life,
+            birth: clamp(birth),
+            survival: clamp(survival),
+            noise: clamp(noise),
+        };
+        rule.name = rule.rulestring();
+        rule
+    }
+
+    pub fn presets() -> Vec<Self> {
+        PRESETS
+            .iter()
+            .map(|&(name, rulestring, birth, survival, noise)| Self {
+                name: String::from(name),
+                ..Self::new(rulestring.parse().unwrap(), birth, survival, noise)
+            })
+            .collect()
+    }
+
+    pub fn probabilities(&self) -> (f64, f64, f64) {
+        (self.birth, self.survival, self.noise)
+    }
+
+    fn rulestring(&self) -> String {
+        format!(
+            "{} pb={} ps={} n={}",
+            self.life, self.birth, self.survival, self.noise
+        )
+    }
+}
+
+impl Rule for Stochastic {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn neighborhood(&self) -> &Neighborhood {
+        self.life.neighborhood()
+    }
+
+    fn step(&self, world: &World, t_cell: Cell) -> Option<Cell> {
+        let neighbors_alive = world.count_alive(t_cell.at, self.neighborhood());
+        let chance = world.random(t_cell.at, TRANSITION);
+
+        let mut alive = if t_cell.is_alive() {
+            self.life.survives(neighbors_alive) && chance < self.survival
+        } else {
+            self.life.is_born(neighbors_alive) && chance < self.birth
+        };
+
+        if self.noise > 0.0 && world.random(t_cell.at, NOISE) < self.noise {
+            alive = !alive;
+        }
+
+        match (t_cell.is_alive(), alive) {
+            (false, true) => Some(Cell {
+                color: SUPER_NOVA,
+                ..t_cell
+            }),
+            (true, false) => Some(Cell {
+                color: DEAD,
+                ..t_cell
+            }),
+            _ => None,
+        }
+    }
+
+    // every generation draws again
+    fn time_invariant(&self) -> bool {
+        false
+    }
+
+    fn metadata(&self) -> Option<RuleMetadata> {
+        Some(RuleMetadata {
+            description: Some(String::from(
+                "life-like rule with birth and survival probabilities and noise",
+            )),
+            author: None,
+            rulestring: Some(self.rulestring()),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn soup(seed: u64) -> World {
+        let mut world = World::new(64, 64);
+        world.set_seed(seed);
+        for cell in world.get_cells() {
+            let (row, col) = cell.at;
+            if (row * 7 + col * 13 + row * col) % 3 == 0 {
+                world.write(Cell { color: SUPER_NOVA, ..cell });
+            }
+        }
+        world
+    }
+
+    fn run(rule: &Stochastic, seed: u64, threads: usize) -> Vec<Cell> {
+        let mut world = soup(seed);
+        world.set_threads(threads);
+        for _ in 0..5 {
+            world.step(rule);
+        }
+        world.get_cells()
+    }
+
+    #[test]
+    fn same_seed_replays() {
+        for rule in Stochastic::presets() {
+            assert_eq!(run(&rule, 3, 1), run(&rule, 3, 1), "{}", rule.name());
+            // the draws are keyed on the cell, not on the thread stepping it
+            assert_eq!(run(&rule, 3, 1), run(&rule, 3, 2), "{}", rule.name());
+            assert_ne!(run(&rule, 3, 1), run(&rule, 4, 1), "{}", rule.name());
+        }
+    }
+
+    #[test]
+    fn certain_transitions_match_life() {
+        let life = LifeLike::conway();
+        let (mut stochastic, mut expected) = (soup(0), soup(0));
+        stochastic.step(&Stochastic::new(life.clone(), 1.0, 1.0, 0.0));
+        expected.step(&life);
+        assert_eq!(stochastic.get_cells(), expected.get_cells());
+    }
+}
@@ -0,0 +1,192 @@
+use crate::cell::{Cell, DEAD};
+use crate::neighborhood::Neighborhood;
+use crate::rules::{Rule, RuleMetadata};
+use crate::world::World;
+use graphics::types::Color;
+
+pub const CONDUCTOR: Color = [1.0, 0.75, 0.0, 1.0];
+pub const HEAD: Color = [0.2, 0.5, 1.0, 1.0];
+pub const TAIL: Color = [1.0, 0.25, 0.1, 1.0];
+
+const BRUSHES: [(&str, Color); 4] = [
+    ("conductor", CONDUCTOR),
+    ("electron head", HEAD),
+    ("electron tail", TAIL),
+    ("empty", DEAD),
+];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum WireState {
+    Empty,
+    Conductor,
+    Head,
+    Tail,
+}
+
+impl WireState {
+    // anything painted that isn't an electron conducts
+    pub fn of(cell: &Cell) -> WireState {
+        if !cell.is_alive() {
+            WireState::Empty
+        } else if cell.color == HEAD {
+            WireState::Head
+        } else if cell.color == TAIL {
+            WireState::Tail
+        } else {
+            WireState::Conductor
+        }
+    }
+
+    pub fn color(&self) -> Color {
+        match self {
+            WireState::Empty => DEAD,
+            WireState::Conductor => CONDUCTOR,
+            WireState::Head => HEAD,
+            WireState::Tail => TAIL,
+        }
+    }
+}
+
+// Electrons running along conductors, a conductor turns into a head
+// next to one or two heads and heads leave a tail behind.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Wireworld {
+    neighborhood: Neighborhood,
+}
+
+impl Default for Wireworld {
+    fn default() -> Self {
+        Self {
+            neighborhood: Neighborhood::moore(1),
+        }
+    }
+}
+
+impl Rule for Wireworld {
+    fn name(&self) -> &str {
+        "Wireworld"
+    }
+
+    fn neighborhood(&self) -> &Neighborhood {
+        &self.neighborhood
+    }
+
+    fn step(&self, world: &World, t_cell: Cell) -> Option<Cell> {
+        let next = match WireState::of(&t_cell) {
+            WireState::Empty => return None,
+            WireState::Head => WireState::Tail,
+            WireState::Tail => WireState::Conductor,
+            WireState::Conductor => {
+                let heads = world
+                    .weighted_sum(t_cell.at, &self.neighborhood, |n| {
+                        if WireState::of(n) == WireState::Head {
+                            1.0
+                        } else {
+                            0.0
+                        }
+                    })
+                    .round() as usize;
+                if heads == 1 || heads == 2 {
+                    WireState::Head
+                } else {
+                    WireState::Conductor
+                }
+            }
+        };
+
+        let color = next.color();
+        if color == t_cell.color {
+            None
+        } else {
+            Some(Cell { color, ..t_cell })
+        }
+    }
+
+    fn brushes(&self) -> &[(&'static str, Color)] {
+        &BRUSHES
+    }
+
+    fn metadata(&self) -> Option<RuleMetadata> {
+        Some(RuleMetadata {
+            description: Some(String::from(
+                "electrons running along conductors, for building logic circuits",
+            )),
+            author: Some(String::from("Brian Silverman")),
+            rulestring: None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::boundary::Boundary;
+
+    // draws a circuit of conductors, row by row, inside a dead border
+    fn circuit(rows: &[&str]) -> World {
+        let mut world = World::new(rows.len() + 2, rows[0].len() + 2);
+        world.set_boundary(Boundary::Dead);
+        for (row, line) in rows.iter().enumerate() {
+            for (col, _) in line.char_indices().filter(|(_, c)| *c == '#') {
+                let cell = world.find_cell_at(row + 1, col + 1).unwrap();
+                world.write(Cell { color: CONDUCTOR, ..cell });
+            }
+        }
+        world
+    }
+
+    fn state(world: &World, (row, col): (usize, usize)) -> WireState {
+        WireState::of(&world.find_cell_at(row + 1, col + 1).unwrap())
+    }
+
+    fn set_head(world: &mut World, (row, col): (usize, usize)) {
+        let cell = world.find_cell_at(row + 1, col + 1).unwrap();
+        world.write(Cell { color: HEAD, ..cell });
+    }
+
+    // the generations an electron takes from one end of a wire to the other, if it gets there
+    fn travel(rows: &[&str], from: (usize, usize), to: (usize, usize)) -> Option<usize> {
+        let rule = Wireworld::default();
+        let mut world = circuit(rows);
+        set_head(&mut world, from);
+        (1..=40).find(|_| {
+            world.step(&rule);
+            state(&world, to) == WireState::Head
+        })
+    }
+
+    #[test]
+    fn electrons_cycle_head_tail_conductor() {
+        let rule = Wireworld::default();
+        let mut world = circuit(&["#####"]);
+        set_head(&mut world, (0, 0));
+
+        world.step(&rule);
+        assert_eq!(state(&world, (0, 0)), WireState::Tail);
+        assert_eq!(state(&world, (0, 1)), WireState::Head);
+        world.step(&rule);
+        assert_eq!(state(&world, (0, 0)), WireState::Conductor);
+        assert_eq!(state(&world, (0, 1)), WireState::Tail);
+        assert_eq!(state(&world, (0, 2)), WireState::Head);
+        // the tail keeps the electron from turning back
+        world.step(&rule);
+        assert_eq!(state(&world, (0, 1)), WireState::Conductor);
+        assert_eq!(state(&world, (0, 3)), WireState::Head);
+    }
+
+    #[test]
+    fn diode_passes_one_way() {
+        let diode = ["     ##     ", "###### #####", "     ##     "];
+        assert_eq!(travel(&diode, (1, 0), (1, 11)), Some(11));
+        assert_eq!(travel(&diode, (1, 11), (1, 0)), None);
+    }
+
+    #[test]
+    fn empty_cells_stay_empty() {
+        let rule = Wireworld::default();
+        let mut world = circuit(&["#  "]);
+        set_head(&mut world, (0, 0));
+        let empty = world.find_cell_at(1, 2).unwrap();
+        assert_eq!(rule.step(&world, empty), None);
+    }
+}
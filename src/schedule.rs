@@ -0,0 +1,43 @@
+// In what order the cells of a generation are updated.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Schedule {
+    // every cell reads the previous generation
+    #[default]
+    Synchronous,
+    // cells update one by one in a shuffled order, seeing earlier updates
+    RandomOrder,
+    // cells update one by one, column after column and top to bottom in each
+    Sweep,
+    // cells with an even row + col update first, the others see them
+    Checkerboard,
+    // every cell takes its update with the given probability
+    Alpha(f64),
+}
+
+impl Schedule {
+    pub const ALL: [Schedule; 5] = [
+        Schedule::Synchronous,
+        Schedule::RandomOrder,
+        Schedule::Sweep,
+        Schedule::Checkerboard,
+        Schedule::Alpha(0.5),
+    ];
+
+    pub fn name(&self) -> String {
+        match self {
+            Schedule::Synchronous => String::from("synchronous"),
+            Schedule::RandomOrder => String::from("random order"),
+            Schedule::Sweep => String::from("sweep"),
+            Schedule::Checkerboard => String::from("checkerboard"),
+            Schedule::Alpha(alpha) => format!("\u{3b1}={}", alpha),
+        }
+    }
+
+    pub fn next(&self) -> Self {
+        let at = Self::ALL
+            .iter()
+            .position(|s| std::mem::discriminant(s) == std::mem::discriminant(self))
+            .unwrap();
+        Self::ALL[(at + 1) % Self::ALL.len()]
+    }
+}
@@ -0,0 +1,314 @@
+use crate::boundary::Boundary;
+use crate::cell::{Cell, DEAD};
+use crate::rules::Rule;
+use crate::schedule::Schedule;
+use crate::topology::Topology;
+use crate::world::World;
+use graphics::types::Color;
+use std::collections::{HashMap, HashSet};
+
+// cells along the side of a tile, even so tiles keep the parity of rows and cols
+pub const TILE: usize = 32;
+
+// the cells of a tile row after row, and how many of them are alive
+struct Tile {
+    cells: Vec<Color>,
+    alive: usize,
+}
+
+impl Tile {
+    fn new() -> Self {
+        Self {
+            cells: vec![DEAD; TILE * TILE],
+            alive: 0,
+        }
+    }
+
+    fn set(&mut self, at: usize, color: Color) {
+        let was_alive = self.cells[at] != DEAD;
+        let alive = color != DEAD;
+        self.cells[at] = color;
+        match (was_alive, alive) {
+            (false, true) => self.alive += 1,
+            (true, false) => self.alive -= 1,
+            _ => {}
+        }
+    }
+}
+
+// An unbounded plane of square tiles, only tiles holding live cells are stored.
+// Every generation steps the stored tiles and the ones next to them,
+// so rules that give birth on empty space only act around the population.
+pub struct SparseWorld {
+    tiles: HashMap<(i64, i64), Tile>,
+    topology: Topology,
+    generation: usize,
+    seed: u64,
+    schedule: Schedule,
+}
+
+impl Default for SparseWorld {
+    fn default() -> Self {
+        Self::new(Topology::default())
+    }
+}
+
+impl SparseWorld {
+    pub fn new(topology: Topology) -> Self {
+        Self {
+            tiles: HashMap::new(),
+            topology,
+            generation: 0,
+            seed: 0,
+            schedule: Schedule::default(),
+        }
+    }
+
+    pub fn topology(&self) -> Topology {
+        self.topology
+    }
+
+    pub fn generation(&self) -> usize {
+        self.generation
+    }
+
+    pub fn set_seed(&mut self, seed: u64) {
+        self.seed = seed;
+    }
+
+    pub fn schedule(&self) -> Schedule {
+        self.schedule
+    }
+
+    // tiles follow the schedule on their own, a sweep goes tile by tile
+    pub fn set_schedule(&mut self, schedule: Schedule) {
+        self.schedule = schedule;
+    }
+
+    pub fn tile_count(&self) -> usize {
+        self.tiles.len()
+    }
+
+    pub fn population(&self) -> usize {
+        self.tiles.values().map(|tile| tile.alive).sum()
+    }
+
+    // the tile holding a cell and where the cell sits in it
+    fn locate(row: i64, col: i64) -> ((i64, i64), usize) {
+        let size = TILE as i64;
+        let key = (row.div_euclid(size), col.div_euclid(size));
+        let at = row.rem_euclid(size) as usize * TILE + col.rem_euclid(size) as usize;
+        (key, at)
+    }
+
+    pub fn get(&self, row: i64, col: i64) -> Color {
+        let (key, at) = Self::locate(row, col);
+        self.tiles.get(&key).map(|tile| tile.cells[at]).unwrap_or(DEAD)
+    }
+
+    pub fn set(&mut self, row: i64, col: i64, color: Color) {
+        // the way Cell::is_alive reads a color
+        let alive = color.iter().take(3).any(|&chanel| chanel > 0.0);
+        let color = if alive { color } else { DEAD };
+        let (key, at) = Self::locate(row, col);
+
+        match self.tiles.get_mut(&key) {
+            Some(tile) => {
+                tile.set(at, color);
+                if tile.alive == 0 {
+                    self.tiles.remove(&key);
+                }
+            }
+            None if alive => {
+                let mut tile = Tile::new();
+                tile.set(at, color);
+                self.tiles.insert(key, tile);
+            }
+            None => {}
+        }
+    }
+
+    // copies the cells of a world placed with its top left cell at origin
+    pub fn import(&mut self, world: &World, (row, col): (i64, i64)) {
+        for cell in world.get_cells() {
+            let (r, c) = cell.at;
+            self.set(row + r as i64, col + c as i64, cell.color);
+        }
+    }
+
+    // fills a world with the cells under it, placed with its top left cell at origin
+    pub fn export(&self, world: &mut World, (row, col): (i64, i64)) {
+        for cell in world.get_cells() {
+            let (r, c) = cell.at;
+            let color = self.get(row + r as i64, col + c as i64);
+            if color != cell.color {
+                world.write(Cell { color, ..cell });
+            }
+        }
+    }
+
+    pub fn step(&mut self, rule: &dyn Rule) {
+        let reach = self.topology.reach(rule.radius());
+        let margin = reach + reach % 2;
+        let side = TILE + margin * 2;
+        let around = margin.div_ceil(TILE) as i64;
+
+        let mut active = HashSet::new();
+        for &(tile_row, tile_col) in self.tiles.keys() {
+            for d_row in -around..=around {
+                for d_col in -around..=around {
+                    active.insert((tile_row + d_row, tile_col + d_col));
+                }
+            }
+        }
+
+        // a dead bordered world the size of a tile and its margin, reused for every tile
+        let mut scratch = World::new(side, side);
+        scratch.set_topology(self.topology);
+        scratch.set_boundary(Boundary::Dead);
+        scratch.set_seed(self.seed);
+        scratch.set_schedule(self.schedule);
+
+        let mut tiles = HashMap::new();
+        for (tile_row, tile_col) in active {
+            let top = tile_row * TILE as i64 - margin as i64;
+            let left = tile_col * TILE as i64 - margin as i64;
+
+            self.export(&mut scratch, (top, left));
+            scratch.set_generation(self.generation);
+            scratch.set_origin((top, left));
+            scratch.step(rule);
+
+            let mut tile = Tile::new();
+            for row in 0..TILE {
+                for col in 0..TILE {
+                    let cell = scratch.find_cell_at(row + margin, col + margin).unwrap();
+                    if cell.is_alive() {
+                        tile.set(row * TILE + col, cell.color);
+                    }
+                }
+            }
+            if tile.alive > 0 {
+                tiles.insert((tile_row, tile_col), tile);
+            }
+        }
+
+        self.tiles = tiles;
+        self.generation += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cell::SUPER_NOVA;
+    use crate::rules::{LifeLike, Stochastic};
+
+    // the same soup on every tile
+    fn periodic_soup(sparse: &mut SparseWorld, tiles: i64) {
+        let side = tiles * TILE as i64;
+        for row in 0..side {
+            for col in 0..side {
+                let (r, c) = (row % TILE as i64, col % TILE as i64);
+                if (r * 7 + c * 13) % 3 == 0 {
+                    sparse.set(row, col, SUPER_NOVA);
+                }
+            }
+        }
+    }
+
+    fn inner(sparse: &SparseWorld, (tile_row, tile_col): (i64, i64)) -> Vec<Color> {
+        let (top, left) = (tile_row * TILE as i64, tile_col * TILE as i64);
+        (8..TILE as i64 - 8)
+            .flat_map(|row| (8..TILE as i64 - 8).map(move |col| (row, col)))
+            .map(|(row, col)| sparse.get(top + row, left + col))
+            .collect()
+    }
+
+    #[test]
+    fn tiles_draw_apart() {
+        let rule = Stochastic::new(LifeLike::conway(), 0.5, 0.5, 0.0);
+        let mut sparse = SparseWorld::default();
+        sparse.set_seed(1);
+        periodic_soup(&mut sparse, 2);
+        assert_eq!(inner(&sparse, (0, 0)), inner(&sparse, (1, 1)));
+
+        sparse.step(&rule);
+        assert_ne!(inner(&sparse, (0, 0)), inner(&sparse, (1, 1)));
+    }
+
+    #[test]
+    fn matches_a_dead_bordered_world() {
+        let rule = LifeLike::conway();
+        let mut world = World::new(96, 96);
+        world.set_boundary(Boundary::Dead);
+        // an R-pentomino far enough from the border not to reach it
+        for &(row, col) in [(47, 48), (47, 49), (48, 47), (48, 48), (49, 48)].iter() {
+            let cell = world.find_cell_at(row, col).unwrap();
+            world.write(Cell { color: SUPER_NOVA, ..cell });
+        }
+        let mut sparse = SparseWorld::default();
+        sparse.import(&world, (0, 0));
+
+        for _ in 0..50 {
+            world.step(&rule);
+            sparse.step(&rule);
+        }
+        let mut exported = World::new(96, 96);
+        sparse.export(&mut exported, (0, 0));
+        assert_eq!(world.get_cells(), exported.get_cells());
+    }
+
+    #[test]
+    fn glider_travels() {
+        let rule = LifeLike::conway();
+        let glider = [(0, 1), (1, 2), (2, 0), (2, 1), (2, 2)];
+        let mut sparse = SparseWorld::default();
+        for &(row, col) in glider.iter() {
+            sparse.set(row + 28, col + 28, SUPER_NOVA);
+        }
+
+        // a glider moves a cell down and right every four generations, here into the next tile
+        for _ in 0..4 * 10 {
+            sparse.step(&rule);
+        }
+        assert_eq!(sparse.population(), 5);
+        assert_eq!(sparse.tile_count(), 1);
+        for &(row, col) in glider.iter() {
+            assert_eq!(sparse.get(row + 38, col + 38), SUPER_NOVA);
+        }
+    }
+
+    #[test]
+    fn tiles_follow_the_schedule() {
+        let rule = LifeLike::conway();
+        let mut sparse = SparseWorld::default();
+        for col in 10..13 {
+            sparse.set(10, col, SUPER_NOVA);
+        }
+        // no cell takes its update, the blinker stays put
+        sparse.set_schedule(Schedule::Alpha(0.0));
+        sparse.step(&rule);
+        assert_eq!(sparse.get(10, 10), SUPER_NOVA);
+
+        sparse.set_schedule(Schedule::Synchronous);
+        sparse.step(&rule);
+        assert_eq!(sparse.get(10, 10), DEAD);
+        assert_eq!(sparse.get(9, 11), SUPER_NOVA);
+    }
+
+    #[test]
+    fn tiles_count_their_live_cells() {
+        let mut sparse = SparseWorld::default();
+        sparse.set(3, 4, SUPER_NOVA);
+        sparse.set(3, 4, SUPER_NOVA);
+        sparse.set(5, -40, SUPER_NOVA);
+        assert_eq!((sparse.population(), sparse.tile_count()), (2, 2));
+
+        // dead writes to an empty tile keep nothing, the last one clearing a tile drops it
+        sparse.set(100, 100, DEAD);
+        sparse.set(3, 4, DEAD);
+        sparse.set(3, 4, DEAD);
+        assert_eq!((sparse.population(), sparse.tile_count()), (1, 1));
+    }
+}
@@ -0,0 +1,200 @@
+use conv::ApproxFrom;
+
+const SQRT_3: f64 = 1.732_050_807_568_877_2;
+
+// Hexagons are pointy-topped and stored in offset rows, odd rows are shifted
+// right by half a cell. Neighborhood offsets on them are read as axial
+// coordinates, where the row below a cell touches its column and the next one.
+// Triangles overlap their neighbors in a row by half a cell, the ones with an
+// even row + col point up.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Topology {
+    #[default]
+    Square,
+    Hexagonal,
+    Triangular,
+}
+
+fn to_f64(n: usize) -> f64 {
+    ApproxFrom::<usize>::approx_from(n).unwrap()
+}
+
+impl Topology {
+    pub const ALL: [Topology; 3] = [Topology::Square, Topology::Hexagonal, Topology::Triangular];
+
+    pub fn name(&self) -> &str {
+        match self {
+            Topology::Square => "square",
+            Topology::Hexagonal => "hexagonal",
+            Topology::Triangular => "triangular",
+        }
+    }
+
+    pub fn points_up(&self, row: usize, col: usize) -> bool {
+        (row + col).is_multiple_of(2)
+    }
+
+    pub fn next(&self) -> Self {
+        let at = Self::ALL.iter().position(|t| t == self).unwrap();
+        Self::ALL[(at + 1) % Self::ALL.len()]
+    }
+
+    // how far the stored neighbors of a radius reach
+    pub fn reach(&self, radius: usize) -> usize {
+        match self {
+            Topology::Square | Topology::Hexagonal => radius,
+            Topology::Triangular => radius * 2,
+        }
+    }
+
+    // Turns a neighborhood offset into an offset in the stored grid,
+    // None when the lattice puts it farther than the radius.
+    pub fn offset(
+        &self,
+        (row, col): (usize, usize),
+        (d_row, d_col): (isize, isize),
+        radius: usize,
+    ) -> Option<(isize, isize)> {
+        match self {
+            Topology::Square => Some((d_row, d_col)),
+            Topology::Hexagonal => {
+                let distance = d_row.abs().max(d_col.abs()).max((d_row - d_col).abs());
+                if distance as usize > radius {
+                    return None;
+                }
+                let half = |row: isize| (row + 1).div_euclid(2);
+                let row = row as isize;
+                Some((d_row, d_col + half(row) - half(row + d_row)))
+            }
+            Topology::Triangular if self.points_up(row, col) => Some((d_row, d_col)),
+            Topology::Triangular => Some((-d_row, d_col)),
+        }
+    }
+
+    // the distance between the rows of cells
+    fn row_height(&self, cell_size: f64) -> f64 {
+        match self {
+            Topology::Square => cell_size,
+            Topology::Hexagonal | Topology::Triangular => cell_size * SQRT_3 / 2.0,
+        }
+    }
+
+    pub fn fit(&self, width: f64, height: f64, cell_size: f64) -> (usize, usize) {
+        let (rows, cols) = match self {
+            Topology::Square => (height / cell_size, width / cell_size),
+            Topology::Hexagonal => {
+                let side = cell_size / SQRT_3;
+                let rows = ((height - side / 2.0) / self.row_height(cell_size)).floor();
+                // wrapping needs pairs of shifted and unshifted rows
+                (rows - rows % 2.0, (width - cell_size / 2.0) / cell_size)
+            }
+            Topology::Triangular => {
+                // both need to be even for up and down triangles to wrap
+                let rows = (height / self.row_height(cell_size)).floor();
+                let cols = (width * 2.0 / cell_size - 1.0).floor();
+                (rows - rows % 2.0, cols - cols % 2.0)
+            }
+        };
+
+        (
+            ApproxFrom::<f64>::approx_from(rows.floor().max(0.0)).unwrap(),
+            ApproxFrom::<f64>::approx_from(cols.floor().max(0.0)).unwrap(),
+        )
+    }
+
+    // width and height a world of rows x cols takes on screen
+    pub fn extent(&self, rows: usize, cols: usize, cell_size: f64) -> (f64, f64) {
+        let (rows, cols) = (to_f64(rows), to_f64(cols));
+        match self {
+            Topology::Square => (cols * cell_size, rows * cell_size),
+            Topology::Hexagonal => (
+                (cols + 0.5) * cell_size,
+                (rows - 1.0).max(0.0) * self.row_height(cell_size) + cell_size * 2.0 / SQRT_3,
+            ),
+            Topology::Triangular => ((cols + 1.0) * cell_size / 2.0, rows * self.row_height(cell_size)),
+        }
+    }
+
+    pub fn cell_origin(&self, row: usize, col: usize, cell_size: f64) -> (f64, f64) {
+        let x = to_f64(col) * cell_size;
+        let y = to_f64(row) * self.row_height(cell_size);
+        match self {
+            Topology::Square => (x, y),
+            Topology::Hexagonal => (x + to_f64(row % 2) * cell_size / 2.0, y),
+            Topology::Triangular => (x / 2.0, y),
+        }
+    }
+
+    // outline of a cell relative to its origin
+    pub fn outline(&self, row: usize, col: usize, cell_size: f64) -> Vec<[f64; 2]> {
+        match self {
+            Topology::Square => vec![
+                [0.0, 0.0],
+                [cell_size, 0.0],
+                [cell_size, cell_size],
+                [0.0, cell_size],
+            ],
+            Topology::Hexagonal => {
+                let side = cell_size / SQRT_3;
+                vec![
+                    [cell_size / 2.0, 0.0],
+                    [cell_size, side / 2.0],
+                    [cell_size, side * 1.5],
+                    [cell_size / 2.0, side * 2.0],
+                    [0.0, side * 1.5],
+                    [0.0, side / 2.0],
+                ]
+            }
+            Topology::Triangular => {
+                let height = self.row_height(cell_size);
+                if self.points_up(row, col) {
+                    vec![[cell_size / 2.0, 0.0], [cell_size, height], [0.0, height]]
+                } else {
+                    vec![[0.0, 0.0], [cell_size, 0.0], [cell_size / 2.0, height]]
+                }
+            }
+        }
+    }
+
+    // the (row, col) under a point, which may lie outside of the world
+    pub fn pick(&self, [x, y]: [f64; 2], cell_size: f64) -> (isize, isize) {
+        match self {
+            Topology::Square => (
+                (y / cell_size).floor() as isize,
+                (x / cell_size).floor() as isize,
+            ),
+            Topology::Hexagonal => {
+                let side = cell_size / SQRT_3;
+                let x = x - cell_size / 2.0;
+                let y = y - side;
+
+                let r = y * 2.0 / 3.0 / side;
+                let q = x / cell_size - r / 2.0;
+                let s = -q - r;
+
+                let (mut rq, mut rr, rs) = (q.round(), r.round(), s.round());
+                let (dq, dr, ds) = ((rq - q).abs(), (rr - r).abs(), (rs - s).abs());
+                if dq > dr && dq > ds {
+                    rq = -rr - rs;
+                } else if dr > ds {
+                    rr = -rq - rs;
+                }
+
+                let row = rr as isize;
+                (row, rq as isize + (row - (row & 1)) / 2)
+            }
+            Topology::Triangular => {
+                let height = self.row_height(cell_size);
+                let row = (y / height).floor();
+                let band = (x * 2.0 / cell_size).floor();
+                // inside the band the point is either in the triangle starting here
+                // or in the one starting half a cell before, split by their shared side
+                let (x, y) = (x * 2.0 / cell_size - band, y / height - row);
+                let up = (row + band).rem_euclid(2.0) == 0.0;
+                let in_band = if up { y >= 1.0 - x } else { y <= x };
+                let col = if in_band { band } else { band - 1.0 };
+                (row as isize, col as isize)
+            }
+        }
+    }
+}
@@ -0,0 +1,57 @@
+use crate::topology::Topology;
+
+// Where the cells of a world land on screen. Worlds only hold the states of
+// their cells, a view places them, so the same world can be drawn at any size.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct View {
+    cell_size: f64,
+    // the screen position of the world's top left corner
+    offset: [f64; 2],
+}
+
+impl Default for View {
+    fn default() -> Self {
+        Self::new(1.0)
+    }
+}
+
+impl View {
+    pub fn new(cell_size: f64) -> Self {
+        Self {
+            cell_size,
+            offset: [0.0, 0.0],
+        }
+    }
+
+    pub fn cell_size(&self) -> f64 {
+        self.cell_size
+    }
+
+    pub fn set_cell_size(&mut self, cell_size: f64) {
+        self.cell_size = cell_size;
+    }
+
+    pub fn offset(&self) -> [f64; 2] {
+        self.offset
+    }
+
+    pub fn set_offset(&mut self, offset: [f64; 2]) {
+        self.offset = offset;
+    }
+
+    // the screen position of a cell's origin
+    pub fn origin(&self, topology: Topology, (row, col): (usize, usize)) -> [f64; 2] {
+        let (x, y) = topology.cell_origin(row, col, self.cell_size);
+        [self.offset[0] + x, self.offset[1] + y]
+    }
+
+    // outline of a cell relative to its origin
+    pub fn outline(&self, topology: Topology, (row, col): (usize, usize)) -> Vec<[f64; 2]> {
+        topology.outline(row, col, self.cell_size)
+    }
+
+    // the cell under a screen position, it may lie outside the world
+    pub fn pick(&self, topology: Topology, [x, y]: [f64; 2]) -> (isize, isize) {
+        topology.pick([x - self.offset[0], y - self.offset[1]], self.cell_size)
+    }
+}
@@ -1,41 +1,243 @@
-use crate::cell::Cell;
-use nalgebra::{Dynamic, Matrix, Point2, Point6, VecStorage};
-use conv::{ApproxFrom};
+use crate::bit_grid::BitGrid;
+use crate::boundary::Boundary;
+use crate::cell::{Cell, DEAD, SUPER_NOVA};
+use crate::neighborhood::Neighborhood;
+use crate::random::Rng;
+use crate::rules::{LifeLike, Rule};
+use crate::schedule::Schedule;
+use crate::topology::Topology;
+use nalgebra::{Dynamic, Matrix, Point2, Point4, VecStorage};
+use rayon::{ThreadPool, ThreadPoolBuilder};
+use std::ops::Range;
+use std::sync::Arc;
 
-pub type WPoint = Point6<f64>;
+// the color of a cell, where it is drawn is up to a view
+pub type WPoint = Point4<f64>;
 pub type MPoint = Point2<usize>;
 type XMatrix<T> = Matrix<T, Dynamic, Dynamic, VecStorage<T, Dynamic, Dynamic>>;
 type WMatrix = XMatrix<WPoint>;
 type MMatrix = XMatrix<MPoint>;
+type SMatrix = XMatrix<Option<MPoint>>;
+
+// how many rows or columns an infinite world adds at once
+const GROWTH: usize = 8;
+
+// fewer cells than this per thread are stepped on one thread
+const MIN_BAND: usize = 1024;
+
+// side of the squares of cells whose changes are tracked between steps
+const TILE: usize = 16;
+
+// salts of the draws the schedules make, clear of the ones rules use
+const SHUFFLE: u64 = u64::MAX;
+const ALPHA: u64 = u64::MAX - 1;
+
+// the tile of a cell in a world of rows, tiles are numbered column after column like the cells
+fn tile_of(rows: usize, row: usize, col: usize) -> usize {
+    row / TILE + col / TILE * rows.div_ceil(TILE)
+}
+
+// How a world keeps its cells for stepping. The dense matrix always holds colors
+// and positions for drawing, packed worlds also keep a bit per cell and step
+// life-like rules on a square synchronous grid a word of cells at a time.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Backend {
+    #[default]
+    Dense,
+    Packed,
+}
+
+impl Backend {
+    pub const ALL: [Backend; 2] = [Backend::Dense, Backend::Packed];
+
+    pub fn name(&self) -> &str {
+        match self {
+            Backend::Dense => "dense",
+            Backend::Packed => "packed",
+        }
+    }
+
+    pub fn next(&self) -> Self {
+        let at = Self::ALL.iter().position(|b| b == self).unwrap();
+        Self::ALL[(at + 1) % Self::ALL.len()]
+    }
+}
 
 pub struct World {
+    // the cells drawn and read, steps fill the back buffer and swap
     matrix: WMatrix,
-    surroundings_matrix: MMatrix,
+    back: WMatrix,
+    // the visiting order of random order steps, kept to reuse its memory
+    order: Vec<usize>,
+    // tiles holding cells that changed since the last step, the tiles every tile
+    // reads through the mirrored edge, and the tiles the next step visits
+    dirty: Vec<bool>,
+    watched: Vec<Vec<usize>>,
+    active: Vec<bool>,
+    surroundings_matrix: SMatrix,
     locations_matrix: MMatrix,
+    boundary: Boundary,
+    topology: Topology,
     edge_width: usize,
+    generation: usize,
+    rng: Rng,
+    // where the top left cell sits on a larger plane, draws are keyed on the plane
+    origin: (i64, i64),
+    schedule: Schedule,
+    backend: Backend,
+    threads: usize,
+    // workers kept across steps, None when stepping on the calling thread
+    pool: Option<Arc<ThreadPool>>,
+    // the live bits of a packed world, None until the next packed step builds them,
+    // and the grid the step after fills
+    packed: Option<BitGrid>,
+    packed_back: Option<BitGrid>,
     cols: usize,
     rows: usize,
 }
 
 impl World {
-    pub fn new(rows: usize, cols: usize, cell_size: f64) -> Self {
+    pub fn new(rows: usize, cols: usize) -> Self {
         let mut instance = Self {
-            matrix: WMatrix::from_element(rows, cols, WPoint::new(0.0, 0.0, 0.0, 0.0, 0.0, 0.0)),
-            surroundings_matrix: MMatrix::from_element(rows + 2, cols + 2, MPoint::new(0, 0)),
+            matrix: WMatrix::from_element(rows, cols, WPoint::origin()),
+            back: WMatrix::from_element(0, 0, WPoint::origin()),
+            order: Vec::new(),
+            dirty: Vec::new(),
+            watched: Vec::new(),
+            active: Vec::new(),
+            surroundings_matrix: SMatrix::from_element(rows + 2, cols + 2, None),
             locations_matrix: MMatrix::from_element(rows, cols, MPoint::new(0, 0)),
+            boundary: Boundary::default(),
+            topology: Topology::default(),
             edge_width: 1,
+            generation: 0,
+            rng: Rng::default(),
+            origin: (0, 0),
+            schedule: Schedule::default(),
+            backend: Backend::default(),
+            threads: 1,
+            pool: None,
+            packed: None,
+            packed_back: None,
             cols,
             rows,
         };
 
         instance.locations_matrix();
-        instance.resize_cells(cell_size);
+        instance.mirror_edge(1);
 
         instance
     }
 
-    pub fn reset(&self, cell_size: f64) -> Self {
-        World::new(self.rows, self.cols, cell_size)
+    pub fn reset(&self) -> Self {
+        self.resized(self.rows, self.cols)
+    }
+
+    // an empty world of another size set up like this one, the workers are shared
+    pub fn resized(&self, rows: usize, cols: usize) -> Self {
+        let mut world = World::new(rows, cols);
+        world.boundary = self.boundary;
+        world.rng = self.rng;
+        world.schedule = self.schedule;
+        world.backend = self.backend;
+        world.threads = self.threads;
+        world.pool = self.pool.clone();
+        world.set_topology(self.topology);
+        world.mirror_edge(self.edge_width);
+        world
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    pub fn generation(&self) -> usize {
+        self.generation
+    }
+
+    // lets a world stand in for a part of a larger one
+    pub fn set_generation(&mut self, generation: usize) {
+        self.generation = generation;
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.rng.seed()
+    }
+
+    pub fn set_seed(&mut self, seed: u64) {
+        self.rng = Rng::new(seed);
+    }
+
+    // the plane position of the top left cell of a world standing in for a part of one
+    pub fn set_origin(&mut self, origin: (i64, i64)) {
+        self.origin = origin;
+    }
+
+    // a reproducible number in [0, 1) for a cell in this generation,
+    // salt tells apart several draws of the same cell
+    pub fn random(&self, (row, col): (usize, usize), salt: u64) -> f64 {
+        let row = self.origin.0.wrapping_add(row as i64) as u64;
+        let col = self.origin.1.wrapping_add(col as i64) as u64;
+        self.rng.sample(&[self.generation as u64, row, col, salt])
+    }
+
+    pub fn schedule(&self) -> Schedule {
+        self.schedule
+    }
+
+    pub fn set_schedule(&mut self, schedule: Schedule) {
+        self.schedule = schedule;
+    }
+
+    pub fn backend(&self) -> Backend {
+        self.backend
+    }
+
+    pub fn set_backend(&mut self, backend: Backend) {
+        self.backend = backend;
+        self.packed = None;
+    }
+
+    pub fn threads(&self) -> usize {
+        self.threads
+    }
+
+    // how many threads synchronous steps are split over
+    pub fn set_threads(&mut self, threads: usize) {
+        if threads.max(1) == self.threads {
+            return;
+        }
+        self.threads = threads.max(1);
+        self.pool = match self.threads {
+            1 => None,
+            threads => ThreadPoolBuilder::new()
+                .num_threads(threads)
+                .build()
+                .ok()
+                .map(Arc::new),
+        };
+    }
+
+    pub fn boundary(&self) -> Boundary {
+        self.boundary
+    }
+
+    pub fn set_boundary(&mut self, boundary: Boundary) {
+        self.boundary = boundary;
+        self.mirror_edge(self.edge_width);
+    }
+
+    pub fn topology(&self) -> Topology {
+        self.topology
+    }
+
+    pub fn set_topology(&mut self, topology: Topology) {
+        self.topology = topology;
+        self.touch();
     }
 
     fn locations_matrix(
@@ -44,18 +246,15 @@ impl World {
        self.locations_matrix = self.matrix.map_with_location(|row, col, _p| MPoint::new(row, col)).clone();
     }
 
-    pub fn resize_cells(&mut self, cell_size: f64) {
-        let mut locations_iter = self.locations_matrix.iter();
+    fn tile_of(&self, row: usize, col: usize) -> usize {
+        tile_of(self.rows, row, col)
+    }
 
-        while let Some(location) = locations_iter.next() {
-            let row = location[0];
-            let col = location[1];
-            let w_point = &mut self.matrix[(row, col)];
-            let x: f64 = ApproxFrom::<usize>::approx_from(col).unwrap();
-            let y: f64 = ApproxFrom::<usize>::approx_from(row).unwrap();
-            w_point[4] = x * cell_size;
-            w_point[5] = y * cell_size;
-        }
+    // makes the next step visit every cell, needed when the rule stepping the world changes
+    pub fn touch(&mut self) {
+        let tiles = self.rows.div_ceil(TILE) * self.cols.div_ceil(TILE);
+        self.dirty.clear();
+        self.dirty.resize(tiles, true);
     }
 
     pub fn get_cells(&self) -> Vec<Cell> {
@@ -83,122 +282,680 @@ impl World {
     }
 
     pub fn write(&mut self, cell: Cell) {
+        if let Some(packed) = self.packed.as_mut() {
+            packed.set(cell.at.0, cell.at.1, cell.is_alive());
+        }
         let (w_point, row, col) = cell.into();
         self.matrix[(row, col)] = w_point;
+        let tile = self.tile_of(row, col);
+        self.dirty[tile] = true;
     }
 
     pub fn mirror_edge(&mut self, edge_width: usize) {
-        let top_slice = self.locations_matrix
-            .slice((self.rows - edge_width, 0), (edge_width, self.cols))
-            .map_with_location(|row, col, loc| Point2::new(Point2::new(row, col + edge_width), loc));
-        let bottom_slice = self.locations_matrix
-            .slice((0, 0), (edge_width, self.cols))
-            .map_with_location(|row, col, loc| {
-                Point2::new(Point2::new(row + self.rows + edge_width, col + edge_width), loc)
-            });
-
-        let left_slice = self.locations_matrix
-            .slice((0, self.cols - edge_width), (self.rows, edge_width))
-            .map_with_location(|row, col, loc| {
-                Point2::new(Point2::new(row + edge_width, col), loc)
-            });
-        let right_slice = self.locations_matrix
-            .slice((0, 0), (self.rows, edge_width))
-            .map_with_location(|row, col, loc| {
-                Point2::new(Point2::new(row + edge_width, col + self.cols + edge_width), loc)
-            });
-        let top_left_slice = self.locations_matrix
-            .slice(
-                (self.rows - edge_width, self.cols - edge_width),
-                (edge_width, edge_width),
-            )
-            .map_with_location(|row, col, loc| {
-                Point2::new(Point2::new(row, col), loc)
-            });
-        let top_right_slice = self.locations_matrix
-            .slice((self.rows - edge_width, 0), (edge_width, edge_width))
-            .map_with_location(|row, col, loc| {
-                Point2::new(Point2::new(row, col + self.cols + edge_width), loc)
-            });
-        let bottom_left_slice = self.locations_matrix
-            .slice((0, self.cols - edge_width), (edge_width, edge_width))
-            .map_with_location(|row, col, loc| {
-                Point2::new(Point2::new(row + self.rows + edge_width, col), loc)
-            });
-        let bottom_right_slice = self.locations_matrix
-            .slice((0, 0), (edge_width, edge_width))
-            .map_with_location(|row, col, loc| {
-                Point2::new(
-                    Point2::new(
-                        row + self.rows + edge_width,
-                        col + self.cols + edge_width,
-                    ),
-                    loc,
-                )
-            });
-
-        let zero_point = MPoint::new(0, 0);
-        let mut mirrored_matrix = self.locations_matrix.clone();
-        mirrored_matrix = mirrored_matrix.insert_columns(0, edge_width, zero_point);
-        mirrored_matrix =
-            mirrored_matrix.insert_columns(self.cols + edge_width , edge_width, zero_point);
-        mirrored_matrix = mirrored_matrix.insert_rows(0, edge_width, zero_point);
-        mirrored_matrix =
-            mirrored_matrix.insert_rows(self.rows + edge_width, edge_width, zero_point);
-
-        let slices = [
-            top_left_slice,
-            top_slice,
-            top_right_slice,
-            right_slice,
-            bottom_right_slice,
-            bottom_slice,
-            bottom_left_slice,
-            left_slice,
-        ];
+        let (rows, cols, boundary) = (self.rows, self.cols, self.boundary);
+        let offset = edge_width as isize;
 
-        for slice in slices.iter() {
-            for point in slice.iter() {
-                let insert_at = (point[0][0], point[0][1]);
-                let location = point[1];
+        self.surroundings_matrix = SMatrix::from_fn(
+            rows + edge_width * 2,
+            cols + edge_width * 2,
+            |row, col| {
+                boundary
+                    .locate(row as isize - offset, col as isize - offset, rows, cols)
+                    .map(|(row, col)| MPoint::new(row, col))
+            },
+        );
+        self.edge_width = edge_width;
+        self.watch_tiles();
+        self.touch();
+    }
 
-                mirrored_matrix[insert_at] = location;
-            }
-        }
+    // finds the tiles the cells of every tile read, wrapping and mirroring as the edge does
+    fn watch_tiles(&mut self) {
+        let side = self.edge_width * 2;
+        let (tile_rows, tile_cols) = (self.rows.div_ceil(TILE), self.cols.div_ceil(TILE));
 
-        self.surroundings_matrix = mirrored_matrix;
-        self.edge_width = edge_width;
+        self.watched = (0..tile_rows * tile_cols)
+            .map(|tile| {
+                let (top, left) = (tile % tile_rows * TILE, tile / tile_rows * TILE);
+                let bottom = (top + TILE).min(self.rows) + side;
+                let right = (left + TILE).min(self.cols) + side;
+
+                let mut tiles: Vec<usize> = (left..right)
+                    .flat_map(|col| (top..bottom).map(move |row| (row, col)))
+                    .filter_map(|at| self.surroundings_matrix[at])
+                    .map(|at| self.tile_of(at[0], at[1]))
+                    .collect();
+                tiles.sort_unstable();
+                tiles.dedup();
+                tiles
+            })
+            .collect();
+    }
+
+    fn border_cell(&self, (row, col): (usize, usize)) -> Cell {
+        let color = match self.boundary {
+            Boundary::Alive => SUPER_NOVA,
+            _ => DEAD,
+        };
+        Cell {
+            color,
+            ..self.cell_at(row, col)
+        }
     }
 
     pub fn get_surroundings(&self, (row, col): (usize, usize)) -> Vec<Cell> {
         let side = self.edge_width * 2 + 1;
 
+        let center = side * side / 2;
+
         let surroundings = self.surroundings_matrix.slice((row, col), (side, side));
 
         surroundings
             .iter()
-            .filter(|at| at[0] != row || at[1] != col)
-            .map(|at| self.cell_at(at[0], at[1]))
+            .enumerate()
+            .filter(|(i, _)| *i != center)
+            .map(|(_, at)| match at {
+                Some(at) => self.cell_at(at[0], at[1]),
+                None => self.border_cell((row, col)),
+            })
             .collect()
     }
 
+    // the neighborhood must fit inside the mirrored edge, see Topology::reach
+    pub fn neighbors<'a>(
+        &'a self,
+        (row, col): (usize, usize),
+        neighborhood: &'a Neighborhood,
+    ) -> impl Iterator<Item = (Cell, f64)> + Clone + 'a {
+        let radius = neighborhood.radius();
+
+        neighborhood
+            .offsets(self.topology)
+            .iter()
+            .filter_map(move |&(d_row, d_col, weight)| {
+                let offset = self.topology.offset((row, col), (d_row, d_col), radius)?;
+                Some((self.relative((row, col), offset), weight))
+            })
+    }
+
+    // the cell at a grid offset, ignoring the topology, it must fit inside the mirrored edge
+    pub fn relative(&self, (row, col): (usize, usize), (d_row, d_col): (isize, isize)) -> Cell {
+        let edge = self.edge_width as isize;
+        let at = (
+            (row as isize + edge + d_row) as usize,
+            (col as isize + edge + d_col) as usize,
+        );
+        match self.surroundings_matrix[at] {
+            Some(at) => self.cell_at(at[0], at[1]),
+            None => self.border_cell((row, col)),
+        }
+    }
+
+    pub fn weighted_sum<F>(&self, at: (usize, usize), neighborhood: &Neighborhood, value: F) -> f64
+    where
+        F: Fn(&Cell) -> f64,
+    {
+        self.neighbors(at, neighborhood)
+            .map(|(cell, weight)| weight * value(&cell))
+            .sum()
+    }
+
+    pub fn count_alive(&self, at: (usize, usize), neighborhood: &Neighborhood) -> usize {
+        self.weighted_sum(at, neighborhood, |cell| if cell.is_alive() { 1.0 } else { 0.0 })
+            .round() as usize
+    }
+
     pub fn next<F>(&self, func: F) -> Vec<Cell>
     where
         F: Fn(Vec<Cell>, Cell) -> Option<Cell>
     {
-        let cells_vec = self.get_cells();
-        let mut cells = cells_vec.iter();
         let mut write_cells = Vec::new();
-        
-        while let Some(cell) = cells.next() {
+
+        for cell in self.get_cells() {
             let surroundings = self.get_surroundings(cell.at);
-            match &func(surroundings, *cell) {
-                Some(c) => {
-                    write_cells.push(*c);
-                },
-                None => {}
+            if let Some(c) = func(surroundings, cell) {
+                write_cells.push(c);
             }
         }
         write_cells
     }
+
+    pub fn step(&mut self, rule: &dyn Rule) {
+        let reach = self.topology.reach(rule.radius());
+        if reach != self.edge_width {
+            self.mirror_edge(reach);
+        }
+
+        let schedule = match rule.synchronous_only() {
+            true => Schedule::Synchronous,
+            false => self.schedule,
+        };
+        let packed = self.backend == Backend::Packed
+            && schedule == Schedule::Synchronous
+            && self.topology == Topology::Square;
+
+        match (packed, rule.as_life_like()) {
+            (true, Some(life)) => self.step_packed(life),
+            _ => self.step_dense(rule, schedule),
+        }
+        self.generation += 1;
+
+        // only a synchronous step of a rule that ignores time leaves the cells it
+        // did not change settled, anything else is stepped in full next time
+        if schedule != Schedule::Synchronous || !rule.time_invariant() {
+            self.touch();
+        }
+
+        if self.boundary == Boundary::Infinite {
+            self.grow(!rule.one_dimensional());
+        }
+    }
+
+    fn step_dense(&mut self, rule: &dyn Rule, schedule: Schedule) {
+        // dense steps leave the bits behind
+        self.packed = None;
+
+        match schedule {
+            Schedule::Synchronous => {
+                self.step_synchronous(rule, rule.time_invariant(), |_, _| true)
+            }
+            Schedule::RandomOrder => {
+                let mut order = std::mem::take(&mut self.order);
+                order.clear();
+                order.extend(0..self.rows * self.cols);
+                // Fisher-Yates, drawing from the seed so the order replays, and from
+                // the origin so the parts of a larger world shuffle apart
+                for i in (1..order.len()).rev() {
+                    let (row, col) = (self.origin.0 as u64, self.origin.1 as u64);
+                    let draw = self.rng.hash(&[self.generation as u64, i as u64, row, col, SHUFFLE]);
+                    order.swap(i, (draw % (i as u64 + 1)) as usize);
+                }
+                let rows = self.rows;
+                self.step_sequential(rule, order.iter().map(|&at| (at % rows, at / rows)));
+                self.order = order;
+            }
+            Schedule::Sweep => {
+                // column after column, the order the matrix stores its cells in
+                let rows = self.rows;
+                self.step_sequential(rule, (0..rows * self.cols).map(|at| (at % rows, at / rows)));
+            }
+            Schedule::Checkerboard => {
+                self.step_synchronous(rule, false, |_, cell| (cell.at.0 + cell.at.1) % 2 == 0);
+                self.step_synchronous(rule, false, |_, cell| (cell.at.0 + cell.at.1) % 2 != 0);
+            }
+            Schedule::Alpha(alpha) => {
+                let alpha = |world: &World, cell: &Cell| world.random(cell.at, ALPHA) < alpha;
+                self.step_synchronous(rule, false, alpha);
+            }
+        }
+    }
+
+    fn step_packed(&mut self, life: &LifeLike) {
+        let mut grid = match self.packed.take() {
+            Some(grid) => grid,
+            None => {
+                let mut grid = BitGrid::new(self.rows, self.cols);
+                for col in 0..self.cols {
+                    for row in 0..self.rows {
+                        grid.set(row, col, self.cell_at(row, col).is_alive());
+                    }
+                }
+                grid
+            }
+        };
+
+        let (mut birth, mut survival) = (0, 0);
+        for n in 0..=8 {
+            birth |= (life.is_born(n) as u16) << n;
+            survival |= (life.survives(n) as u16) << n;
+        }
+
+        let mut next = match self.packed_back.take() {
+            Some(next) if (next.rows(), next.cols()) == (self.rows, self.cols) => next,
+            _ => BitGrid::new(self.rows, self.cols),
+        };
+
+        grid.fill_halo(self.boundary);
+        grid.step(birth, survival, &mut next);
+
+        let (rows, matrix, dirty) = (self.rows, &mut self.matrix, &mut self.dirty);
+        grid.changes(&next, |row, col, alive| {
+            let w_point = &mut matrix[(row, col)];
+            let color = if alive { SUPER_NOVA } else { DEAD };
+            for (i, &chanel) in color.iter().enumerate() {
+                w_point[i] = chanel as f64;
+            }
+            dirty[tile_of(rows, row, col)] = true;
+        });
+        self.packed = Some(next);
+        self.packed_back = Some(grid);
+    }
+
+    // Updates the cells passing the filter, all reading the world as it was. Steps
+    // fill the back buffer from the front one and swap them, bands of cells go to
+    // the workers of the pool, so the result is the same whatever the thread count.
+    // A step on one thread does not allocate, handing bands to the pool allocates a
+    // task for each. Skipping quiet tiles visits only the ones reading a changed
+    // tile, the back buffer already holds the others.
+    fn step_synchronous<F>(&mut self, rule: &dyn Rule, skip_quiet: bool, filter: F)
+    where
+        F: Fn(&World, &Cell) -> bool + Sync,
+    {
+        let empty = WMatrix::from_element(0, 0, WPoint::origin());
+        let mut back = std::mem::replace(&mut self.back, empty);
+        if back.shape() != self.matrix.shape() {
+            back = self.matrix.clone();
+        }
+
+        let mut active = std::mem::take(&mut self.active);
+        active.clear();
+        active.extend(
+            self.watched
+                .iter()
+                .map(|tiles| !skip_quiet || tiles.iter().any(|&tile| self.dirty[tile])),
+        );
+
+        let world = &*self;
+        let rows = self.rows;
+        let active_ref = &active;
+        let step_band = |start: usize, band: &mut [WPoint]| {
+            let mut offset = 0;
+            while offset < band.len() {
+                let (row, col) = ((start + offset) % rows, (start + offset) / rows);
+                // the rest of this column of the tile that falls in the band
+                let end = (offset + TILE - row % TILE)
+                    .min(offset + rows - row)
+                    .min(band.len());
+
+                if active_ref[world.tile_of(row, col)] {
+                    for (i, w_point) in band[offset..end].iter_mut().enumerate() {
+                        let cell = world.cell_at(row + i, col);
+                        let next = match filter(world, &cell) {
+                            true => rule.step(world, cell),
+                            false => None,
+                        };
+                        *w_point = match next {
+                            Some(next) => next.into(),
+                            None => world.matrix[(row + i, col)],
+                        };
+                    }
+                }
+                offset = end;
+            }
+        };
+
+        let cells = back.len();
+        match &self.pool {
+            Some(pool) if cells >= self.threads * MIN_BAND => {
+                let band = cells.div_ceil(self.threads);
+                pool.scope(|scope| {
+                    for (i, chunk) in back.as_mut_slice().chunks_mut(band).enumerate() {
+                        let step_band = &step_band;
+                        scope.spawn(move |_| step_band(i * band, chunk));
+                    }
+                });
+            }
+            _ => step_band(0, back.as_mut_slice()),
+        }
+
+        // the visited tiles where a cell changed are the ones the next step reads around
+        let (rows, cols, front) = (self.rows, self.cols, &self.matrix);
+        let tile_rows = rows.div_ceil(TILE);
+        for (tile, dirty) in self.dirty.iter_mut().enumerate() {
+            let (top, left) = (tile % tile_rows * TILE, tile / tile_rows * TILE);
+            *dirty = active[tile]
+                && (left..(left + TILE).min(cols)).any(|col| {
+                    (top..(top + TILE).min(rows)).any(|row| back[(row, col)] != front[(row, col)])
+                });
+        }
+
+        self.active = active;
+        self.back = std::mem::replace(&mut self.matrix, back);
+    }
+
+    // updates the cells one by one, each seeing the ones before it
+    fn step_sequential<I>(&mut self, rule: &dyn Rule, order: I)
+    where
+        I: Iterator<Item = (usize, usize)>,
+    {
+        for (row, col) in order {
+            let cell = self.cell_at(row, col);
+            if let Some(cell) = rule.step(self, cell) {
+                self.write(cell);
+            }
+        }
+    }
+
+    // adds room on every side that live cells came within reach of, or only on
+    // the left and right when the rows are the history of a 1D rule
+    fn grow(&mut self, vertical: bool) {
+        let margin = self.edge_width;
+        let (rows, cols) = (self.rows, self.cols);
+        let alive = |rows: Range<usize>, mut cols: Range<usize>| {
+            cols.any(|col| rows.clone().any(|row| self.cell_at(row, col).is_alive()))
+        };
+
+        // only the cells within the margin are looked at
+        let top = vertical && alive(0..margin.min(rows), 0..cols);
+        let bottom = vertical && alive(rows.saturating_sub(margin)..rows, 0..cols);
+        let left = alive(0..rows, 0..margin.min(cols));
+        let right = alive(0..rows, cols.saturating_sub(margin)..cols);
+
+        if !(top || bottom || left || right) {
+            return;
+        }
+
+        // an even growth keeps which rows hexagons shift and which way triangles point
+        let growth = GROWTH.max(margin + margin % 2);
+        let mut matrix = self.matrix.clone();
+
+        if top {
+            matrix = matrix.insert_rows(0, growth, WPoint::origin());
+        }
+        if bottom {
+            let at = matrix.nrows();
+            matrix = matrix.insert_rows(at, growth, WPoint::origin());
+        }
+        if left {
+            matrix = matrix.insert_columns(0, growth, WPoint::origin());
+        }
+        if right {
+            let at = matrix.ncols();
+            matrix = matrix.insert_columns(at, growth, WPoint::origin());
+        }
+
+        self.rows = matrix.nrows();
+        self.cols = matrix.ncols();
+        self.matrix = matrix;
+        self.packed = None;
+        self.locations_matrix();
+        self.mirror_edge(self.edge_width);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rules::{Generations, Immigration, LargerThanLife, Lenia};
+    use std::alloc::{GlobalAlloc, Layout, System};
+    use std::cell::Cell as Counter;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    // counts the allocations of every thread, tests run side by side
+    struct Counting;
+
+    thread_local! {
+        static ALLOCATIONS: Counter<usize> = const { Counter::new(0) };
+    }
+
+    unsafe impl GlobalAlloc for Counting {
+        unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+            let _ = ALLOCATIONS.try_with(|count| count.set(count.get() + 1));
+            System.alloc(layout)
+        }
+
+        unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+            System.dealloc(ptr, layout)
+        }
+    }
+
+    #[global_allocator]
+    static ALLOCATOR: Counting = Counting;
+
+    fn allocations() -> usize {
+        ALLOCATIONS.with(|count| count.get())
+    }
+
+    // a fixed random soup, about a third of the cells alive
+    fn soup(rows: usize, cols: usize) -> World {
+        let mut world = World::new(rows, cols);
+        world.set_seed(1);
+        for cell in world.get_cells() {
+            if world.random(cell.at, 0) < 1.0 / 3.0 {
+                world.write(Cell { color: SUPER_NOVA, ..cell });
+            }
+        }
+        world
+    }
+
+    #[test]
+    fn threads_match_one_thread() {
+        let rules: Vec<Box<dyn Rule>> = vec![
+            Box::new(LifeLike::conway()),
+            Box::new(LargerThanLife::bugs()),
+            Box::new(Generations::brians_brain()),
+            Box::new(Lenia::orbium()),
+        ];
+
+        for rule in rules.iter() {
+            let mut one = soup(64, 64);
+            let mut many = soup(64, 64);
+            many.set_threads(4);
+            assert!(many.pool.is_some());
+
+            for _ in 0..2 {
+                one.step(rule.as_ref());
+                many.step(rule.as_ref());
+                assert_eq!(one.get_cells(), many.get_cells(), "{}", rule.name());
+            }
+            assert!(one.get_cells().iter().any(|cell| cell.is_alive()), "{}", rule.name());
+        }
+    }
+
+    #[test]
+    fn packed_matches_dense() {
+        for life in LifeLike::presets() {
+            for &boundary in Boundary::ALL.iter() {
+                for &(rows, cols) in [(40, 50), (67, 13)].iter() {
+                    let mut dense = soup(rows, cols);
+                    let mut packed = soup(rows, cols);
+                    dense.set_boundary(boundary);
+                    packed.set_boundary(boundary);
+                    packed.set_backend(Backend::Packed);
+
+                    for _ in 0..10 {
+                        dense.step(&life);
+                        packed.step(&life);
+                    }
+                    assert_eq!(
+                        dense.get_cells(),
+                        packed.get_cells(),
+                        "{} {:?} {}x{}",
+                        life.name(),
+                        boundary,
+                        rows,
+                        cols
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn growth_keeps_row_parity() {
+        let mut world = World::new(10, 10);
+        world.set_topology(Topology::Hexagonal);
+        world.set_boundary(Boundary::Infinite);
+        world.mirror_edge(9);
+        let cell = world.find_cell_at(0, 5).unwrap();
+        world.write(Cell { color: SUPER_NOVA, ..cell });
+
+        // the cell is within reach of the top, left and right, each grows by 10 rather than 9
+        world.grow(true);
+        assert_eq!((world.rows(), world.cols()), (20, 30));
+        assert!(world.find_cell_at(10, 15).unwrap().is_alive());
+    }
+
+    // writes the order cells are visited in into their red chanel
+    struct Visits {
+        neighborhood: Neighborhood,
+        count: AtomicUsize,
+    }
+
+    impl Rule for Visits {
+        fn name(&self) -> &str {
+            "visits"
+        }
+
+        fn neighborhood(&self) -> &Neighborhood {
+            &self.neighborhood
+        }
+
+        fn step(&self, _world: &World, cell: Cell) -> Option<Cell> {
+            let visit = self.count.fetch_add(1, Ordering::SeqCst);
+            Some(Cell { color: [visit as f32, 0.0, 0.0, 1.0], ..cell })
+        }
+    }
+
+    fn visits(schedule: Schedule, seed: u64) -> Vec<(usize, usize, usize)> {
+        let rule = Visits {
+            neighborhood: Neighborhood::moore(1),
+            count: AtomicUsize::new(0),
+        };
+        let mut world = World::new(5, 7);
+        world.set_seed(seed);
+        world.set_schedule(schedule);
+        world.step(&rule);
+        world
+            .get_cells()
+            .iter()
+            .map(|cell| (cell.at.0, cell.at.1, cell.color[0] as usize))
+            .collect()
+    }
+
+    #[test]
+    fn sweep_goes_column_after_column() {
+        for (row, col, visit) in visits(Schedule::Sweep, 0) {
+            assert_eq!(visit, col * 5 + row, "{} {}", row, col);
+        }
+    }
+
+    #[test]
+    fn checkerboard_steps_even_cells_first() {
+        let visits = visits(Schedule::Checkerboard, 0);
+        let half = |even: bool| {
+            visits
+                .iter()
+                .filter(move |(row, col, _)| ((row + col) % 2 == 0) == even)
+                .map(|&(_, _, visit)| visit)
+        };
+        assert!(half(true).max() < half(false).min());
+    }
+
+    #[test]
+    fn random_order_visits_every_cell_once() {
+        let mut order: Vec<usize> = visits(Schedule::RandomOrder, 0).iter().map(|v| v.2).collect();
+        assert_ne!(order, (0..35).collect::<Vec<_>>());
+        order.sort_unstable();
+        assert_eq!(order, (0..35).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn random_schedules_replay_under_a_seed() {
+        // the order cells are visited in, and for alpha which of them are
+        for &schedule in [Schedule::RandomOrder, Schedule::Alpha(0.5)].iter() {
+            let run = visits(schedule, 5);
+            assert_eq!(run, visits(schedule, 5), "{:?}", schedule);
+            assert_ne!(run, visits(schedule, 6), "{:?}", schedule);
+        }
+    }
+
+    #[test]
+    fn resized_worlds_share_the_workers() {
+        let mut world = World::new(10, 10);
+        world.set_threads(3);
+        let pool = world.pool.clone().unwrap();
+
+        let resized = world.resized(20, 30);
+        assert_eq!((resized.rows(), resized.cols(), resized.threads()), (20, 30, 3));
+        assert!(Arc::ptr_eq(&pool, resized.pool.as_ref().unwrap()));
+
+        // asking for the same count keeps the pool too
+        world.set_threads(3);
+        assert!(Arc::ptr_eq(&pool, world.pool.as_ref().unwrap()));
+    }
+
+    #[test]
+    fn steps_on_one_thread_do_not_allocate() {
+        let mut rules: Vec<(Box<dyn Rule>, Backend)> = vec![
+            (Box::new(LifeLike::conway()), Backend::Dense),
+            (Box::new(LifeLike::conway()), Backend::Packed),
+            (Box::new(Generations::brians_brain()), Backend::Dense),
+            (Box::new(LargerThanLife::bugs()), Backend::Dense),
+        ];
+        for rule in Immigration::presets() {
+            rules.push((Box::new(rule), Backend::Dense));
+        }
+
+        for (rule, backend) in rules.iter() {
+            let mut world = soup(64, 64);
+            world.set_backend(*backend);
+            // the first steps set up the buffers
+            world.step(rule.as_ref());
+            world.step(rule.as_ref());
+
+            let before = allocations();
+            for _ in 0..3 {
+                world.step(rule.as_ref());
+            }
+            assert_eq!(allocations() - before, 0, "{} {:?}", rule.name(), backend);
+        }
+    }
+
+    #[test]
+    fn skipping_quiet_tiles_matches_full_steps() {
+        // a random patch by the top right corner, the rest of the world starts quiet
+        let patch = |boundary: Boundary, topology: Topology| {
+            let mut world = World::new(64, 80);
+            world.set_seed(2);
+            world.set_topology(topology);
+            world.set_boundary(boundary);
+            for cell in world.get_cells() {
+                let (row, col) = cell.at;
+                if row < 10 && col >= 68 && world.random(cell.at, 0) < 0.4 {
+                    world.write(Cell { color: SUPER_NOVA, ..cell });
+                }
+            }
+            world
+        };
+        let (life, bugs) = (LifeLike::conway(), LargerThanLife::bugs());
+        let boundaries = [
+            Boundary::Torus,
+            Boundary::KleinBottle,
+            Boundary::Reflective,
+            Boundary::Dead,
+        ];
+        // Life everywhere, the wide reach of Bugs on every boundary and every lattice
+        let mut cases: Vec<(&dyn Rule, Boundary, Topology)> = Vec::new();
+        for &boundary in boundaries.iter() {
+            for &topology in Topology::ALL.iter() {
+                cases.push((&life, boundary, topology));
+            }
+            cases.push((&bugs, boundary, Topology::Square));
+        }
+        cases.push((&bugs, Boundary::Torus, Topology::Hexagonal));
+        cases.push((&bugs, Boundary::Torus, Topology::Triangular));
+
+        for (rule, boundary, topology) in cases {
+            let (mut skipping, mut full) = (patch(boundary, topology), patch(boundary, topology));
+            let mut skipped = false;
+
+            for step in 0..3 {
+                skipping.step(rule);
+                skipped |= skipping.active.contains(&false);
+                full.touch();
+                full.step(rule);
+                assert_eq!(
+                    skipping.get_cells(),
+                    full.get_cells(),
+                    "{} {:?} {:?} step {}",
+                    rule.name(),
+                    boundary,
+                    topology,
+                    step
+                );
+            }
+            assert!(skipped, "{} {:?} {:?}", rule.name(), boundary, topology);
+        }
+    }
 }
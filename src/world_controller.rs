@@ -1,16 +1,39 @@
-use crate::cell::Cell;
-use crate::world::World;
+use crate::ant::{Ant, Heading, Turmite};
+use crate::boundary::Boundary;
+use crate::random::Rng;
+use crate::schedule::Schedule;
+use crate::sparse_world::SparseWorld;
+use crate::cell::{Cell, DEAD, SUPER_NOVA};
+use crate::hashlife::{HashLife, MAX_STEP_LOG};
+use crate::rules::{
+    Cellulose, Elementary, Generations, Immigration, LargerThanLife, Lenia, LifeLike, Margolus, RgbLife,
+    Rule, SmoothLife, Stochastic, Wireworld,
+};
+use crate::topology::Topology;
+use crate::view::View;
+use crate::world::{Backend, World};
 use graphics::types::Color;
 use opengl_graphics::GlGraphics;
 use palette::{Gradient, Hsv, LinSrgba};
 use piston::input::{Button, ButtonArgs, Input, Key, Motion, MouseButton, RenderArgs, ResizeArgs};
 use piston::{ButtonState, Event, Loop};
+use std::thread;
 use std::vec::IntoIter;
-use conv::{ApproxFrom};
 
-const SUPER_NOVA: [f32;4] = [1.0;4];
-const DEAD: [f32;4] = [0.0;4];
+const ANT: Color = [1.0, 0.2, 0.0, 1.0];
 
+// a triangle inside the cell pointing where the ant heads
+fn ant_marker(heading: Heading, cell_size: f64) -> [[f64; 2]; 3] {
+    let (low, mid, high) = (cell_size * 0.2, cell_size * 0.5, cell_size * 0.8);
+    match heading {
+        Heading::North => [[mid, low], [high, high], [low, high]],
+        Heading::East => [[high, mid], [low, high], [low, low]],
+        Heading::South => [[mid, high], [low, low], [high, low]],
+        Heading::West => [[low, mid], [high, low], [high, high]],
+    }
+}
+
+#[derive(Clone, Copy)]
 enum CursorAction {
     Paint,
     Clear,
@@ -18,11 +41,21 @@ enum CursorAction {
 
 pub struct WorldController {
     world: World,
-    cell_size: f64,
-    frame_size: usize,
+    // when set the world is a window onto it
+    sparse: Option<SparseWorld>,
+    window_size: [f64; 2],
+    view: View,
+    rules: Vec<Box<dyn Rule>>,
+    active_rule: usize,
+    turmites: Vec<Turmite>,
+    active_turmite: usize,
+    ants: Vec<Ant>,
     cursor: Option<Cell>,
     cursor_colors_iter: IntoIter<Color>,
     cursor_action: Option<CursorAction>,
+    brush: usize,
+    seed: u64,
+    jump_log: u32,
     paused: bool,
     speed: isize,
 }
@@ -53,54 +86,359 @@ fn cursor_colors_iter() -> IntoIter<Color> {
     colors_vec.into_iter()
 }
 
+impl Default for WorldController {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl WorldController {
     pub fn new() -> Self {
-        let world = World::new(0, 0, 0.0);
+        let seed = Rng::from_time().seed();
+        let mut world = World::new(0, 0);
+        world.set_seed(seed);
+        world.set_threads(thread::available_parallelism().map(|n| n.get()).unwrap_or(1));
+        let mut rules: Vec<Box<dyn Rule>> = vec![Box::new(Cellulose::default())];
+        for rule in LifeLike::presets() {
+            rules.push(Box::new(rule));
+        }
+        for rule in Generations::presets() {
+            rules.push(Box::new(rule));
+        }
+        for rule in LargerThanLife::presets() {
+            rules.push(Box::new(rule));
+        }
+        for rule in Elementary::presets() {
+            rules.push(Box::new(rule));
+        }
+        for rule in RgbLife::presets() {
+            rules.push(Box::new(rule));
+        }
+        for rule in Immigration::presets() {
+            rules.push(Box::new(rule));
+        }
+        for rule in Lenia::presets() {
+            rules.push(Box::new(rule));
+        }
+        for rule in SmoothLife::presets() {
+            rules.push(Box::new(rule));
+        }
+        for rule in Margolus::presets() {
+            rules.push(Box::new(rule));
+        }
+        rules.push(Box::new(Wireworld::default()));
+        for rule in Stochastic::presets() {
+            rules.push(Box::new(rule));
+        }
 
         Self {
             world,
-            cell_size: 0.0,
+            sparse: None,
+            window_size: [0.0, 0.0],
+            view: View::new(0.0),
             cursor: None,
             cursor_colors_iter: cursor_colors_iter(),
             cursor_action: None,
-            frame_size: 1,
+            brush: 0,
+            seed,
+            jump_log: 10,
+            rules,
+            active_rule: 0,
+            turmites: Turmite::presets(),
+            active_turmite: 0,
+            ants: Vec::new(),
             paused: true,
             speed: 1
         }
     }
 
-    fn size_world(width: f64, height: f64) -> (usize, usize, f64) {
-        let cell_size = Self::get_cell_size(width, height);
-        let rows: f64 = height / cell_size;
-        let cols: f64 = width / cell_size;
-        (
-            ApproxFrom::<f64>::approx_from(rows).unwrap(), // count rows
-            ApproxFrom::<f64>::approx_from(cols).unwrap(),  // count columns
-            cell_size,
+    pub fn rule(&self) -> &dyn Rule {
+        self.rules[self.active_rule].as_ref()
+    }
+
+    fn rule_mut(&mut self) -> &mut dyn Rule {
+        self.rules[self.active_rule].as_mut()
+    }
+
+    pub fn add_rule(&mut self, rule: Box<dyn Rule>) -> usize {
+        self.rules.push(rule);
+        self.rules.len() - 1
+    }
+
+    pub fn set_rule(&mut self, rule: Box<dyn Rule>) {
+        let index = self.add_rule(rule);
+        self.select_rule(index);
+    }
+
+    pub fn select_rule(&mut self, index: usize) {
+        if index < self.rules.len() {
+            self.active_rule = index;
+            self.select_brush(0);
+            // cells the old rule left settled may not be for the new one
+            self.world.touch();
+        }
+    }
+
+    // picks one of the rule's brushes for the cursor
+    pub fn select_brush(&mut self, index: usize) {
+        let color = match self.rule().brushes().get(index) {
+            Some((_, color)) => *color,
+            None => return,
+        };
+        self.brush = index;
+        if let Some(cursor) = self.cursor.as_mut() {
+            cursor.color = color;
+        }
+    }
+
+    fn next_rule(&mut self) {
+        self.select_rule((self.active_rule + 1) % self.rules.len());
+    }
+
+    pub fn turmite(&self) -> &Turmite {
+        &self.turmites[self.active_turmite]
+    }
+
+    pub fn add_ant(&mut self, ant: Ant) {
+        self.ants.push(ant);
+    }
+
+    pub fn ants(&self) -> &[Ant] {
+        &self.ants
+    }
+
+    fn next_turmite(&mut self) {
+        self.active_turmite = (self.active_turmite + 1) % self.turmites.len();
+        for ant in self.ants.iter_mut() {
+            ant.state = 0;
+        }
+    }
+
+    fn set_radius(&mut self, radius: usize) {
+        self.rule_mut().set_radius(radius);
+        self.world.touch();
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    // the world keeps stepping from where it is with the new seed
+    pub fn set_seed(&mut self, seed: u64) {
+        self.seed = seed;
+        self.world.set_seed(seed);
+        if let Some(sparse) = self.sparse.as_mut() {
+            sparse.set_seed(seed);
+        }
+    }
+
+    // how many generations a jump skips, 2^jump_log
+    pub fn set_jump_log(&mut self, jump_log: u32) {
+        self.jump_log = jump_log.min(MAX_STEP_LOG);
+    }
+
+    // HashLife steps an unbounded plane, only a square world with a dead border or an
+    // infinite one steps the same way, a sparse world would lose what is off screen
+    pub fn can_jump(&self) -> bool {
+        self.world.topology() == Topology::Square
+            && matches!(self.world.boundary(), Boundary::Dead | Boundary::Infinite)
+            && self.sparse.is_none()
+            && self.ants.is_empty()
+            && self.rule().as_life_like().is_some()
+    }
+
+    // fast-forwards life-like rules 2^jump_log generations
+    pub fn jump(&mut self) {
+        if !self.can_jump() {
+            return;
+        }
+        let mut hashlife = match self.rule().as_life_like().and_then(HashLife::new) {
+            Some(hashlife) => hashlife,
+            None => return,
+        };
+
+        hashlife.import(&self.world);
+        hashlife.set_step_log(self.jump_log);
+        hashlife.step();
+        hashlife.export(&mut self.world);
+        // later draws must not reuse the generations skipped over
+        let generation = self.world.generation().wrapping_add(hashlife.generation() as usize);
+        self.world.set_generation(generation);
+    }
+
+    fn sparse_world(&self, topology: Topology) -> SparseWorld {
+        let mut sparse = SparseWorld::new(topology);
+        sparse.set_seed(self.seed);
+        sparse.set_schedule(self.world.schedule());
+        sparse
+    }
+
+    // switches between the bounded world and an unbounded sparse one seen through it
+    pub fn toggle_sparse(&mut self) {
+        self.sparse = match self.sparse {
+            Some(_) => None,
+            None => {
+                let mut sparse = self.sparse_world(self.world.topology());
+                sparse.import(&self.world, (0, 0));
+                Some(sparse)
+            }
+        };
+    }
+
+    // empties the world, and the plane behind it when sparse
+    pub fn clear(&mut self) {
+        if self.sparse.is_some() {
+            self.sparse = Some(self.sparse_world(self.world.topology()));
+        }
+        self.build_world(self.world.boundary(), self.world.topology());
+    }
+
+    pub fn set_backend(&mut self, backend: Backend) {
+        self.world.set_backend(backend);
+    }
+
+    pub fn set_threads(&mut self, threads: usize) {
+        self.world.set_threads(threads);
+    }
+
+    pub fn set_schedule(&mut self, schedule: Schedule) {
+        self.world.set_schedule(schedule);
+        if let Some(sparse) = self.sparse.as_mut() {
+            sparse.set_schedule(schedule);
+        }
+    }
+
+    pub fn set_boundary(&mut self, boundary: Boundary) {
+        if boundary != Boundary::Infinite && self.world.boundary() == Boundary::Infinite {
+            self.build_world(boundary, self.world.topology());
+        } else {
+            self.world.set_boundary(boundary);
+        }
+    }
+
+    pub fn set_topology(&mut self, topology: Topology) {
+        self.build_world(self.world.boundary(), topology);
+    }
+
+    pub fn title(&self) -> String {
+        let running = if self.ants.is_empty() {
+            self.rule().name()
+        } else {
+            self.turmite().name()
+        };
+        let boundary = self.world.boundary();
+        let jump = match self.can_jump() {
+            true => format!("jump 2^{}", self.jump_log),
+            false => String::from("no jump"),
+        };
+        format!(
+            "cellulose - {} - {} {} - {} {} x{} - seed {} - {}",
+            running,
+            self.world.topology().name(),
+            match self.sparse {
+                Some(_) => "sparse",
+                None => boundary.name(),
+            },
+            self.world.schedule().name(),
+            self.world.backend().name(),
+            self.world.threads(),
+            self.seed,
+            jump
         )
     }
 
-    fn set_cursor(&mut self, [x, y]: [f64; 2]) {
-        let col: usize = ApproxFrom::<f64>::approx_from(x / self.cell_size).unwrap();
-        let row: usize = ApproxFrom::<f64>::approx_from(y / self.cell_size).unwrap();
+    fn build_world(&mut self, boundary: Boundary, topology: Topology) {
+        let [width, height] = self.window_size;
+        let (rows, cols, cell_size) = Self::size_world(width, height, topology);
+        // keeps the seed, schedule, backend and thread pool, resizes come in bursts
+        self.world = self.world.resized(rows, cols);
+        self.ants.clear();
+        // the cell under the cursor may not be in the new world
+        self.cursor = None;
+        self.view = View::new(cell_size);
+        self.world.set_topology(topology);
+        self.world.set_boundary(boundary);
+        self.world.mirror_edge(topology.reach(self.rule().radius()));
+
+        // the plane outlives the window, which shows its new part of it,
+        // only another lattice starts a new one
+        if self.sparse.as_ref().is_some_and(|sparse| sparse.topology() != topology) {
+            self.sparse = Some(self.sparse_world(topology));
+        }
+        if let Some(sparse) = self.sparse.as_ref() {
+            sparse.export(&mut self.world, (0, 0));
+        }
+    }
 
-        match self.world.find_cell_at(row, col) {
-            Some(cell) => {
-                let color = match &self.cursor {
-                    Some(c) => c.color,
-                    None => SUPER_NOVA,
-                };
-                self.cursor = Some(Cell { color, ..cell });
-                if (row, col) != cell.at {
-                    self.cursor_colors_iter = cursor_colors_iter();
-                }
+    // an infinite world outgrows the window, scale it back in
+    fn fit_world(&mut self) {
+        let [width, height] = self.window_size;
+        let (world_width, world_height) =
+            self.world
+                .topology()
+                .extent(self.world.rows(), self.world.cols(), 1.0);
+        let cell_size = (width / world_width).min(height / world_height);
+
+        if cell_size < self.view.cell_size() {
+            self.view.set_cell_size(cell_size);
+        }
+    }
+
+    fn size_world(width: f64, height: f64, topology: Topology) -> (usize, usize, f64) {
+        let cell_size = Self::get_cell_size(width, height);
+        let (rows, cols) = topology.fit(width, height, cell_size);
+        (rows, cols, cell_size)
+    }
+
+    fn set_cursor(&mut self, position: [f64; 2]) {
+        let (row, col) = self.view.pick(self.world.topology(), position);
+        if row < 0 || col < 0 {
+            return;
+        }
+        let (row, col) = (row as usize, col as usize);
+
+        if let Some(cell) = self.world.find_cell_at(row, col) {
+            let color = match (&self.cursor, self.rule().brushes().get(self.brush)) {
+                (_, Some((_, color))) => *color,
+                (Some(c), None) => c.color,
+                (None, None) => SUPER_NOVA,
+            };
+            self.cursor = Some(Cell { color, ..cell });
+            if (row, col) != cell.at && self.rule().brushes().is_empty() {
+                self.cursor_colors_iter = cursor_colors_iter();
             }
-            None => {}
         }
     }
 
-    fn flow_cursor_color(&mut self, [_x, _y]: [f64; 2]) {
-        if let Some(mut cell) = self.cursor.as_mut() {
+    // paints or clears the cell under the cursor, if it is still in the world
+    fn apply_cursor(&mut self, action: CursorAction) {
+        let cursor = match self.cursor {
+            Some(cursor) => cursor,
+            None => return,
+        };
+        if self.world.find_cell_at(cursor.at.0, cursor.at.1).is_none() {
+            return;
+        }
+        let color = match action {
+            CursorAction::Paint => cursor.color,
+            CursorAction::Clear => DEAD,
+        };
+        self.world.write(Cell { color, ..cursor });
+        if let Some(sparse) = self.sparse.as_mut() {
+            let (row, col) = cursor.at;
+            sparse.set(row as i64, col as i64, color);
+        }
+    }
+
+    fn flow_cursor_color(&mut self, [_x, y]: [f64; 2]) {
+        let brushes = self.rule().brushes().len();
+        if brushes > 0 {
+            let step = if y < 0.0 { brushes - 1 } else { 1 };
+            self.select_brush((self.brush + step) % brushes);
+            return;
+        }
+
+        if let Some(cell) = self.cursor.as_mut() {
             match self.cursor_colors_iter.next() {
                 Some(c) => cell.color = c,
                 None => self.cursor_colors_iter = cursor_colors_iter(),
@@ -112,13 +450,11 @@ impl WorldController {
         match e {
             Event::Loop(lp) => match lp {
                 Loop::Render(args) => {
-                    self.render(&args, gl);
+                    self.render(args, gl);
                 }
-                Loop::Update(_) => {
-                    if !self.paused {
-                        for _i in 0 .. self.speed {
-                            self.update();
-                        }
+                Loop::Update(_) if !self.paused => {
+                    for _i in 0 .. self.speed {
+                        self.update();
                     }
                 }
                 _ => {}
@@ -128,25 +464,13 @@ impl WorldController {
                     window_size,
                     draw_size: _,
                 }) => {
-                    let (rows, cols, cell_size) =
-                        Self::size_world(window_size[0], window_size[1]);
-                    self.world = World::new(rows, cols, cell_size);
-                    self.cell_size = cell_size;
-                    self.world.mirror_edge(self.frame_size);
+                    self.window_size = *window_size;
+                    self.build_world(self.world.boundary(), self.world.topology());
                 }
                 Input::Move(motion) => match motion {
                     Motion::MouseCursor(position) => {
-                        if let Some(action) = &self.cursor_action {
-                            match action {
-                                CursorAction::Paint => self.world.write(self.cursor.unwrap()),
-                                CursorAction::Clear => {
-                                    let cursor = self.cursor.unwrap();
-                                    self.world.write(Cell {
-                                        color: DEAD,
-                                        ..cursor
-                                    });
-                                }
-                            }
+                        if let Some(action) = self.cursor_action {
+                            self.apply_cursor(action);
                         }
                         self.set_cursor(*position);
                     }
@@ -162,9 +486,7 @@ impl WorldController {
                         MouseButton::Left => {
                             if state == &ButtonState::Press {
                                 self.cursor_action = Some(CursorAction::Paint);
-                                if let Some(c) = self.cursor {
-                                    self.world.write(c);
-                                }
+                                self.apply_cursor(CursorAction::Paint);
                             } else {
                                 self.cursor_action = None;
                             }
@@ -172,11 +494,7 @@ impl WorldController {
                         MouseButton::Right => {
                             if state == &ButtonState::Press {
                                 self.cursor_action = Some(CursorAction::Clear);
-                                let cursor = self.cursor.unwrap();
-                                self.world.write(Cell {
-                                    color: DEAD,
-                                    ..cursor
-                                });
+                                self.apply_cursor(CursorAction::Clear);
                             } else {
                                 self.cursor_action = None
                             }
@@ -191,24 +509,66 @@ impl WorldController {
                             self.speed -= 1;
                         }
                         Key::Up => {
-                            self.frame_size += 1;
-                            self.world.mirror_edge(self.frame_size);
+                            self.set_radius(self.rule().radius() + 1);
                         }
                         Key::Down => {
-                            if self.frame_size > 2 {
-                                self.frame_size -= 1;
-                            }
-                            else {
-                                self.frame_size = 1;
-                            }
-                            self.world.mirror_edge(self.frame_size);
+                            self.set_radius(self.rule().radius().saturating_sub(1).max(1));
+                        }
+                        Key::Tab if state == &ButtonState::Press => {
+                            self.next_rule();
                         }
                         Key::Space => {
                             self.paused = state == &ButtonState::Release;
                         }
                         Key::C => {
-                            self.world = self.world.reset(self.cell_size);
-                            self.world.mirror_edge(self.frame_size);
+                            self.clear();
+                        }
+                        Key::T if state == &ButtonState::Press => {
+                            self.set_topology(self.world.topology().next());
+                        }
+                        Key::B if state == &ButtonState::Press => {
+                            self.set_boundary(self.world.boundary().next());
+                        }
+                        Key::S if state == &ButtonState::Press => {
+                            self.toggle_sparse();
+                        }
+                        Key::P if state == &ButtonState::Press => {
+                            self.set_backend(self.world.backend().next());
+                        }
+                        Key::Period if state == &ButtonState::Press => {
+                            self.set_threads(self.world.threads() + 1);
+                        }
+                        Key::Comma if state == &ButtonState::Press => {
+                            self.set_threads(self.world.threads() - 1);
+                        }
+                        Key::U if state == &ButtonState::Press => {
+                            self.set_schedule(self.world.schedule().next());
+                        }
+                        Key::J if state == &ButtonState::Press => {
+                            self.jump();
+                        }
+                        Key::RightBracket if state == &ButtonState::Press => {
+                            self.set_jump_log(self.jump_log + 1);
+                        }
+                        Key::LeftBracket if state == &ButtonState::Press => {
+                            self.set_jump_log(self.jump_log.saturating_sub(1));
+                        }
+                        Key::R if state == &ButtonState::Press => {
+                            self.set_seed(Rng::from_time().seed());
+                        }
+                        Key::A if state == &ButtonState::Press => {
+                            if let Some(cell) = self.cursor {
+                                self.add_ant(Ant::new(cell.at, Heading::North));
+                            }
+                        }
+                        Key::N if state == &ButtonState::Press => {
+                            self.next_turmite();
+                        }
+                        Key::D1 | Key::D2 | Key::D3 | Key::D4 | Key::D5 | Key::D6 | Key::D7
+                        | Key::D8 | Key::D9
+                            if state == &ButtonState::Press =>
+                        {
+                            self.select_brush(*k as usize - Key::D1 as usize);
                         }
                         _ => {}
                     },
@@ -238,124 +598,156 @@ impl WorldController {
 
         *result
             .iter()
-            .filter(|n| n > &&15.0_f64)
-            .next()
+            .find(|n| n > &&15.0_f64)
             .unwrap_or(&15.0_f64)
     }
 
     pub fn render(&mut self, args: &RenderArgs, gl: &mut GlGraphics) {
         use graphics::*;
 
-        let square = rectangle::square(0.0, 0.0, self.cell_size as f64);
-        let cells_vec = self.world.get_cells();
-        let mut cells = cells_vec.iter();
-        let cursor = self.cursor.clone();
+        let view = self.view;
+        let square = rectangle::square(0.0, 0.0, view.cell_size());
+        let topology = self.world.topology();
+        let rule = self.rule();
+        let cells: Vec<Cell> = self
+            .world
+            .get_cells()
+            .into_iter()
+            .map(|cell| Cell {
+                color: rule.paint(&cell),
+                ..cell
+            })
+            .collect();
+        let cursor = self.cursor;
+        let ants: Vec<(Cell, Heading)> = self
+            .ants
+            .iter()
+            .filter_map(|ant| Some((self.world.find_cell_at(ant.at.0, ant.at.1)?, ant.heading)))
+            .collect();
 
         gl.draw(args.viewport(), |c, gl| {
             clear(DEAD, gl);
 
-            while let Some(Cell {
-                color,
-                top_left,
-                at: _,
-            }) = cells.next()
-            {
-                let transform = c.transform.trans(top_left[0], top_left[1]);
-                let rect = Rectangle::new(*color);
-                rect.draw(square, &c.draw_state, transform, gl)
+            let mut draw_cell = |Cell { color, at }: Cell| {
+                let [x, y] = view.origin(topology, at);
+                let transform = c.transform.trans(x, y);
+                match topology {
+                    Topology::Square => {
+                        Rectangle::new(color).draw(square, &c.draw_state, transform, gl)
+                    }
+                    _ => {
+                        let outline = view.outline(topology, at);
+                        Polygon::new(color).draw(&outline, &c.draw_state, transform, gl)
+                    }
+                }
+            };
+
+            for cell in cells {
+                draw_cell(cell);
             }
 
-            if let Some(Cell {
-                color,
-                top_left,
-                at: _,
-            }) = cursor
-            {
-                let transform = c.transform.trans(top_left[0], top_left[1]);
-                let rect = Rectangle::new(color);
-                rect.draw(square, &c.draw_state, transform, gl)
+            if let Some(cell) = cursor {
+                draw_cell(cell);
+            }
+
+            for (cell, heading) in ants {
+                let [x, y] = view.origin(topology, cell.at);
+                let transform = c.transform.trans(x, y);
+                let marker = ant_marker(heading, view.cell_size());
+                Polygon::new(ANT).draw(&marker, &c.draw_state, transform, gl);
             }
         });
     }
 
+    // while ants walk the rule rests, they are the only ones changing cells
     pub fn update(&mut self) {
-        enum Chanels {
-            Red,
-            Green,
-            Blue
-        }
-
-        fn is_alive(cell: &Cell) -> bool {
-            is_chanel_alive(cell, &Chanels::Red)
-            || is_chanel_alive(cell, &Chanels::Green)
-            || is_chanel_alive(cell, &Chanels::Blue)
-        }
-
-        fn _is_super_nova(cell: &Cell) -> bool {
-            let [r, g, b, a] = cell.color;
-            (r + g + b) * a >= 3.0
+        if !self.ants.is_empty() {
+            let turmite = &self.turmites[self.active_turmite];
+            for ant in self.ants.iter_mut() {
+                let (row, col) = ant.at;
+                ant.step(turmite, &mut self.world);
+                // the plane takes the cell the ant left behind
+                let cell = self.world.find_cell_at(row, col);
+                if let (Some(sparse), Some(cell)) = (self.sparse.as_mut(), cell) {
+                    sparse.set(row as i64, col as i64, cell.color);
+                }
+            }
+            return;
         }
 
-        fn is_chanel_alive(cell: &Cell, ch: &Chanels) -> bool {
-            let [r, g, b, a] = cell.color;
-            match ch {
-                Chanels::Red => r * a > 0.0,
-                Chanels::Green => g * a > 0.0,
-                Chanels::Blue => b * a > 0.0
-            }
+        let rule = self.rules[self.active_rule].as_ref();
+        if let Some(sparse) = self.sparse.as_mut() {
+            // what is drawn goes to the plane as well, so only the step comes back
+            sparse.step(rule);
+            sparse.export(&mut self.world, (0, 0));
+            return;
         }
+        self.world.step(rule);
 
-        fn _is_chanel_growing(cell: &Cell, ch: &Chanels) -> bool {
-            let [r, g, b, a] = cell.color;
-            match ch {
-                Chanels::Red => r * a > 0.5,
-                Chanels::Green => g * a > 0.5,
-                Chanels::Blue => b * a > 0.5
-            }
+        if self.world.boundary() == Boundary::Infinite {
+            self.fit_world();
         }
+    }
+}
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rules::LifeLike;
+
+    fn controller(boundary: Boundary) -> WorldController {
+        let mut controller = WorldController::new();
+        controller.window_size = [640.0, 480.0];
+        controller.set_rule(Box::new(LifeLike::conway()));
+        controller.build_world(boundary, Topology::Square);
+        controller
+    }
 
+    #[test]
+    fn jumps_advance_the_generation() {
+        let mut controller = controller(Boundary::Dead);
+        controller.set_jump_log(10);
+        controller.world.set_generation(3);
+        controller.jump();
+        assert_eq!(controller.world.generation(), 3 + 1024);
+    }
 
+    #[test]
+    fn jumps_need_an_unbounded_plane() {
+        let mut controller = controller(Boundary::Torus);
+        assert!(!controller.can_jump());
+        controller.jump();
+        assert_eq!(controller.world.generation(), 0);
+        assert!(controller.title().ends_with("no jump"));
 
-        let the_rule = |neighbors: Vec<Cell>, t_cell: Cell| {
-            let alive = is_alive(&t_cell);
-            let neighbors_alive = neighbors.iter().filter(|n| is_alive(*n));
-            if neighbors_alive.clone().count() >= self.frame_size * 4 {
-                if alive {
-                    Some(Cell {
-                        color: DEAD,
-                        ..t_cell
-                    })
-                } else {
-                    None
-                }
-            } else if neighbors_alive.clone().count() >= self.frame_size * 3 {
-                if !alive {
-                    Some(Cell {
-                        color: SUPER_NOVA,
-                        ..t_cell
-                    })
-                } else {
-                    None
-                }
-            } else if neighbors_alive.count() < self.frame_size * 2 {
-                if alive {                                                                                       
-                    Some(Cell {
-                        color: DEAD,
-                        ..t_cell
-                    })
-                } else {
-                    None
-                }
-            } else {
-                None
-            }
-        };
-        let write_cells = self.world.next(the_rule);
-        let mut write_cells_iter = write_cells.iter();
+        controller.set_boundary(Boundary::Infinite);
+        assert!(controller.can_jump());
+    }
 
-        while let Some(w_c) = write_cells_iter.next() {
-            self.world.write(*w_c);
-        }
+    #[test]
+    fn sparse_planes_outlive_resizes() {
+        let mut controller = controller(Boundary::Torus);
+        controller.toggle_sparse();
+        // painted on screen, and far off it
+        controller.cursor = controller.world.find_cell_at(2, 3).map(|cell| Cell {
+            color: SUPER_NOVA,
+            ..cell
+        });
+        controller.apply_cursor(CursorAction::Paint);
+        controller.sparse.as_mut().unwrap().set(-500, 900, SUPER_NOVA);
+
+        controller.window_size = [320.0, 240.0];
+        controller.build_world(Boundary::Torus, Topology::Square);
+        let sparse = controller.sparse.as_ref().unwrap();
+        assert_eq!(sparse.population(), 2);
+        assert!(controller.world.find_cell_at(2, 3).unwrap().is_alive());
+
+        controller.clear();
+        assert_eq!(controller.sparse.as_ref().unwrap().population(), 0);
+
+        // a new lattice starts a new plane
+        controller.sparse.as_mut().unwrap().set(-500, 900, SUPER_NOVA);
+        controller.set_topology(Topology::Hexagonal);
+        assert_eq!(controller.sparse.as_ref().unwrap().population(), 0);
     }
 }